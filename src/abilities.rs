@@ -0,0 +1,161 @@
+use std::fmt;
+
+/// Which kind of rest is being taken by the encounter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rest {
+    Short,
+    Long,
+}
+
+/// When a spent use comes back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+    /// "(Recharge after a Short or Long Rest)"
+    ShortRest,
+    /// "(3/Day)", "1/day each: ..."
+    LongRest,
+}
+
+impl fmt::Display for Recovery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recovery::ShortRest => write!(f, "per rest"),
+            Recovery::LongRest => write!(f, "per day"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitedUse {
+    pub label: String,
+    pub max: u32,
+    pub remaining: u32,
+    pub recovery: Recovery,
+}
+
+impl LimitedUse {
+    pub fn new(label: &str, max: u32, recovery: Recovery) -> Self {
+        Self {
+            label: label.to_string(),
+            max,
+            remaining: max,
+            recovery,
+        }
+    }
+
+    /// Spend one use, returns false if there was nothing left to spend.
+    pub fn spend(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+
+    pub fn restore(&mut self) {
+        if self.remaining < self.max {
+            self.remaining += 1;
+        }
+    }
+
+    pub fn recovers_on(&self, rest: Rest) -> bool {
+        match rest {
+            Rest::Long => true,
+            Rest::Short => self.recovery == Recovery::ShortRest,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.remaining = self.max;
+    }
+}
+
+impl fmt::Display for LimitedUse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}/{} {})",
+            self.label, self.remaining, self.max, self.recovery
+        )
+    }
+}
+
+/// Find the usage limits of a special ability or action.
+///
+/// The limit is either part of the name, e.g. "Legendary Resistance (3/Day)",
+/// or, for innate spellcasting, one line per frequency in the description,
+/// e.g. "3/day each: lightning bolt, ice storm".
+pub fn parse_limited_uses(name: &str, desc: &str) -> Vec<LimitedUse> {
+    if let Some(limited_use) = parse_name(name) {
+        return vec![limited_use];
+    }
+    desc.lines().flat_map(parse_per_day_line).collect()
+}
+
+fn parse_name(name: &str) -> Option<LimitedUse> {
+    let open = name.rfind('(')?;
+    let close = name[open..].find(')')? + open;
+    let label = name[..open].trim();
+    let inner = name[open + 1..close].to_lowercase();
+
+    if inner.starts_with("recharge") && inner.contains("short") && inner.contains("rest") {
+        return Some(LimitedUse::new(label, 1, Recovery::ShortRest));
+    }
+    let max = parse_per_day(&inner)?;
+    Some(LimitedUse::new(label, max, Recovery::LongRest))
+}
+
+/// Parse "3/day" and "3/day each" into 3.
+fn parse_per_day(s: &str) -> Option<u32> {
+    let (count, rest) = s.trim().split_once('/')?;
+    if !rest.trim().to_lowercase().starts_with("day") {
+        return None;
+    }
+    count.trim().parse().ok()
+}
+
+fn parse_per_day_line(line: &str) -> Vec<LimitedUse> {
+    let Some((frequency, spells)) = line.split_once(':') else {
+        return Vec::new();
+    };
+    let Some(max) = parse_per_day(frequency) else {
+        return Vec::new();
+    };
+    let spells = split_spells(spells);
+    if spells.is_empty() {
+        return Vec::new();
+    }
+
+    if frequency.to_lowercase().contains("each") {
+        spells
+            .iter()
+            .map(|spell| LimitedUse::new(spell, max, Recovery::LongRest))
+            .collect()
+    } else {
+        vec![LimitedUse::new(&spells.join(", "), max, Recovery::LongRest)]
+    }
+}
+
+/// Split a comma separated spell list while keeping notes such as
+/// "planar ally (1d4 + 1 lemures, or 1 bearded devil)" in one piece.
+pub fn split_spells(list: &str) -> Vec<String> {
+    let mut spells = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in list.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                spells.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    spells.push(current.trim().to_string());
+    spells.retain(|spell| !spell.is_empty());
+    spells
+}
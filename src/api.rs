@@ -1,4 +1,7 @@
 use crate::creature::ApiCreatureSearchItem;
+use crate::encounter::EncounterTemplate;
+use crate::reference::{ApiReference, ReferenceKind};
+use crate::spells::{spell_slug_from_url, ApiSpell};
 use reqwest::{Client, Error as ReqwestError, Response};
 use serde_json::{from_str, Error as SerdeError, Value};
 use std::fmt;
@@ -57,6 +60,108 @@ impl ApiCall for MonsterSearch {
     }
 }
 
+pub trait MonsterFilterApiCall {
+    fn monster_filter(
+        &self,
+        filter: &MonsterFilter,
+    ) -> impl std::future::Future<Output = Result<Response, ReqwestError>> + Send;
+}
+
+/// Monsters within a challenge rating range, optionally of one type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonsterFilter {
+    pub cr_min: f64,
+    pub cr_max: f64,
+    pub creature_type: Option<String>,
+}
+
+impl MonsterFilter {
+    pub fn query(&self) -> String {
+        let mut query = format!("cr__gte={}&cr__lte={}", self.cr_min, self.cr_max);
+        if let Some(creature_type) = &self.creature_type {
+            query.push_str(&format!("&type__iexact={creature_type}"));
+        }
+        query.push_str("&limit=100");
+        query
+    }
+}
+
+pub struct MonsterFilterSearch;
+
+impl MonsterFilterApiCall for MonsterFilterSearch {
+    async fn monster_filter(&self, filter: &MonsterFilter) -> Result<Response, ReqwestError> {
+        let client = Client::new();
+        client
+            .get(format!("{API_BASE_URL}/monsters/?{}", filter.query()))
+            .send()
+            .await
+    }
+}
+
+pub trait SpellApiCall {
+    fn spell_search(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Response, ReqwestError>> + Send;
+
+    fn spell_fetch(
+        &self,
+        slug: &str,
+    ) -> impl std::future::Future<Output = Result<Response, ReqwestError>> + Send;
+}
+
+pub struct SpellSearch;
+
+impl SpellApiCall for SpellSearch {
+    async fn spell_search(&self, name: &str) -> Result<Response, ReqwestError> {
+        let client = Client::new();
+        client
+            .get(format!("{API_BASE_URL}/spells/?search={name}"))
+            .send()
+            .await
+    }
+
+    async fn spell_fetch(&self, slug: &str) -> Result<Response, ReqwestError> {
+        let client = Client::new();
+        client
+            .get(format!("{API_BASE_URL}/spells/{slug}/"))
+            .send()
+            .await
+    }
+}
+
+pub trait ReferenceApiCall {
+    fn condition_search(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Response, ReqwestError>> + Send;
+
+    fn section_search(
+        &self,
+        name: &str,
+    ) -> impl std::future::Future<Output = Result<Response, ReqwestError>> + Send;
+}
+
+pub struct ReferenceSearch;
+
+impl ReferenceApiCall for ReferenceSearch {
+    async fn condition_search(&self, name: &str) -> Result<Response, ReqwestError> {
+        let client = Client::new();
+        client
+            .get(format!("{API_BASE_URL}/conditions/?search={name}"))
+            .send()
+            .await
+    }
+
+    async fn section_search(&self, name: &str) -> Result<Response, ReqwestError> {
+        let client = Client::new();
+        client
+            .get(format!("{API_BASE_URL}/sections/?search={name}"))
+            .send()
+            .await
+    }
+}
+
 pub async fn search_for_creature<T: ApiCall>(
     api: &T,
     name: &str,
@@ -70,7 +175,137 @@ pub async fn search_for_creature<T: ApiCall>(
     Ok(parsed_data)
 }
 
+pub async fn search_for_creatures_by_cr<T: MonsterFilterApiCall>(
+    api: &T,
+    filter: &MonsterFilter,
+) -> Result<Vec<ApiCreatureSearchItem>, ApiError> {
+    let creatures_resp = api
+        .monster_filter(filter)
+        .await
+        .map_err(ApiError::Request)?;
+    let resp_str = creatures_resp
+        .text()
+        .await
+        .map_err(ApiError::ResponseText)?;
+    let parsed_data = parse_json_response(&resp_str)?;
+    Ok(parsed_data)
+}
+
 pub fn parse_json_response(data: &str) -> Result<Vec<ApiCreatureSearchItem>, SerdeError> {
     let val: Value = from_str(data)?;
     serde_json::from_value(val["results"].clone())
 }
+
+pub async fn search_for_spell<T: SpellApiCall>(
+    api: &T,
+    name: &str,
+) -> Result<Vec<ApiSpell>, ApiError> {
+    let spells_resp = api.spell_search(name).await.map_err(ApiError::Request)?;
+    let resp_str = spells_resp.text().await.map_err(ApiError::ResponseText)?;
+    let parsed_data = parse_spell_json_response(&resp_str)?;
+    Ok(parsed_data)
+}
+
+pub fn parse_spell_json_response(data: &str) -> Result<Vec<ApiSpell>, SerdeError> {
+    let val: Value = from_str(data)?;
+    serde_json::from_value(val["results"].clone())
+}
+
+pub async fn fetch_spell<T: SpellApiCall>(api: &T, slug: &str) -> Result<ApiSpell, ApiError> {
+    let spell_resp = api.spell_fetch(slug).await.map_err(ApiError::Request)?;
+    let resp_str = spell_resp.text().await.map_err(ApiError::ResponseText)?;
+    let parsed_data = from_str(&resp_str)?;
+    Ok(parsed_data)
+}
+
+/// The spells of a spell list that could be fetched, and why the others
+/// could not.
+#[derive(Debug, Default)]
+pub struct SpellList {
+    pub spells: Vec<ApiSpell>,
+    pub errors: Vec<ApiError>,
+}
+
+/// Resolve the spell URLs of a monster's `spell_list` against the spells
+/// endpoint, a spell that fails doesn't stop the others.
+pub async fn fetch_spell_list<T: SpellApiCall>(api: &T, spell_list: &[String]) -> SpellList {
+    let mut result = SpellList::default();
+    for url in spell_list {
+        let Some(slug) = spell_slug_from_url(url) else {
+            let e = ApiError::Unexpected(format!("Not a spell URL: {url}"));
+            result.errors.push(e);
+            continue;
+        };
+        match fetch_spell(api, slug).await {
+            Ok(spell) => result.spells.push(spell),
+            Err(e) => result.errors.push(e),
+        }
+    }
+    result
+}
+
+/// The stat blocks of the monsters of an encounter with their counts.
+pub type EncounterMonsters = Vec<(ApiCreatureSearchItem, u32)>;
+
+/// Look up the stat block of every monster of the template, preferring the
+/// search result with exactly the same name.
+pub async fn fetch_encounter_monsters<T: ApiCall>(
+    api: &T,
+    template: &EncounterTemplate,
+) -> Result<EncounterMonsters, ApiError> {
+    let mut monsters = Vec::new();
+    for monster in &template.monsters {
+        let mut results = search_for_creature(api, &monster.name).await?;
+        let exact = results
+            .iter()
+            .position(|result| result.name.eq_ignore_ascii_case(&monster.name));
+        let creature = match exact {
+            Some(i) => results.swap_remove(i),
+            None if !results.is_empty() => results.swap_remove(0),
+            None => {
+                return Err(ApiError::Unexpected(format!(
+                    "No monster named {}",
+                    monster.name
+                )))
+            }
+        };
+        monsters.push((creature, monster.count));
+    }
+    Ok(monsters)
+}
+
+/// Search both the conditions and the rules sections, conditions first.
+pub async fn search_reference<T: ReferenceApiCall>(
+    api: &T,
+    name: &str,
+) -> Result<Vec<ApiReference>, ApiError> {
+    let conditions_resp = api
+        .condition_search(name)
+        .await
+        .map_err(ApiError::Request)?;
+    let resp_str = conditions_resp
+        .text()
+        .await
+        .map_err(ApiError::ResponseText)?;
+    let mut references = parse_reference_json_response(&resp_str, ReferenceKind::Condition)?;
+
+    let sections_resp = api.section_search(name).await.map_err(ApiError::Request)?;
+    let resp_str = sections_resp.text().await.map_err(ApiError::ResponseText)?;
+    references.extend(parse_reference_json_response(
+        &resp_str,
+        ReferenceKind::Rule,
+    )?);
+    Ok(references)
+}
+
+pub fn parse_reference_json_response(
+    data: &str,
+    kind: ReferenceKind,
+) -> Result<Vec<ApiReference>, SerdeError> {
+    let val: Value = from_str(data)?;
+    let mut references: Vec<ApiReference> = serde_json::from_value(val["results"].clone())?;
+    for reference in references.iter_mut() {
+        reference.kind = kind;
+    }
+    Ok(references)
+}
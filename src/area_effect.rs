@@ -0,0 +1,143 @@
+use crate::checks::Ability;
+use crate::creature::CreatureItem;
+use std::fmt;
+
+/// An effect forcing a saving throw on everyone in its area, e.g. a fireball.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AreaEffect {
+    pub ability: Ability,
+    pub dc: i64,
+    pub damage: u64,
+    pub damage_type: Option<String>,
+    pub half_on_success: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defense {
+    Resistant,
+    Immune,
+    Vulnerable,
+}
+
+impl fmt::Display for Defense {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Defense::Resistant => write!(f, "resistant"),
+            Defense::Immune => write!(f, "immune"),
+            Defense::Vulnerable => write!(f, "vulnerable"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveResult {
+    pub name: String,
+    pub ability: Ability,
+    pub roll: i64,
+    pub bonus: i64,
+    pub dc: i64,
+    pub success: bool,
+    pub damage: u64,
+    pub defense: Option<Defense>,
+}
+
+impl fmt::Display for SaveResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} save {} ({:+}) = {} vs DC {}, {}",
+            self.name,
+            self.ability,
+            self.roll,
+            self.bonus,
+            self.roll + self.bonus,
+            self.dc,
+            if self.success { "success" } else { "failure" }
+        )?;
+        write!(f, ", takes {} damage", self.damage)?;
+        if let Some(defense) = self.defense {
+            write!(f, " ({defense})")?;
+        }
+        Ok(())
+    }
+}
+
+/// How the creature's resistances, immunities and vulnerabilities treat the
+/// damage type. Conditional clauses like "bludgeoning, piercing, and slashing
+/// from nonmagical attacks" depend on the attack, so they are left to the DM.
+pub fn defense_against(creature: &CreatureItem, damage_type: &str) -> Option<Defense> {
+    let damage_type = damage_type.trim().to_lowercase();
+    if damage_type.is_empty() {
+        return None;
+    }
+    let lists_type = |list: &Option<String>| {
+        list.as_ref()
+            .is_some_and(|list| listed_damage_types(list).any(|listed| listed == damage_type))
+    };
+    if lists_type(&creature.damage_immunities) {
+        Some(Defense::Immune)
+    } else if lists_type(&creature.damage_resistances) {
+        Some(Defense::Resistant)
+    } else if lists_type(&creature.damage_vulnerabilities) {
+        Some(Defense::Vulnerable)
+    } else {
+        None
+    }
+}
+
+/// The damage types of the unconditional clauses of a list like "cold;
+/// bludgeoning, piercing, and slashing from nonmagical attacks".
+fn listed_damage_types(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(';')
+        .filter(|clause| !is_conditional(clause))
+        .flat_map(|clause| clause.split(','))
+        .map(|item| {
+            let item = item.trim().to_lowercase();
+            item.strip_prefix("and ")
+                .unwrap_or(&item)
+                .trim()
+                .to_string()
+        })
+}
+
+fn is_conditional(clause: &str) -> bool {
+    let clause = clause.to_lowercase();
+    [" from ", "nonmagical", "that aren't", "except"]
+        .iter()
+        .any(|condition| clause.contains(condition))
+}
+
+pub fn apply_defense(damage: u64, defense: Option<Defense>) -> u64 {
+    match defense {
+        Some(Defense::Immune) => 0,
+        Some(Defense::Resistant) => damage / 2,
+        Some(Defense::Vulnerable) => damage * 2,
+        None => damage,
+    }
+}
+
+/// Resolve the saving throw of one creature given its d20 roll.
+pub fn resolve_save(creature: &CreatureItem, effect: &AreaEffect, roll: i64) -> SaveResult {
+    let bonus = effect.ability.save_bonus(creature);
+    let success = roll + bonus >= effect.dc;
+    let damage = match (success, effect.half_on_success) {
+        (false, _) => effect.damage,
+        (true, true) => effect.damage / 2,
+        (true, false) => 0,
+    };
+    let defense = effect
+        .damage_type
+        .as_deref()
+        .and_then(|damage_type| defense_against(creature, damage_type));
+
+    SaveResult {
+        name: creature.name.clone(),
+        ability: effect.ability,
+        roll,
+        bonus,
+        dc: effect.dc,
+        success,
+        damage: apply_defense(damage, defense),
+        defense,
+    }
+}
@@ -0,0 +1,154 @@
+use crate::creature::ApiCreatureSearchItem;
+use crate::difficulty::{party_thresholds, rate_encounter, xp_for, Difficulty, EncounterSummary};
+use crate::encounter::{EncounterMonster, EncounterTemplate};
+use std::fmt;
+
+/// The most monsters of one kind a suggestion will contain.
+const MAX_COUNT: u32 = 12;
+
+/// The XP the party can take on at the difficulty.
+pub fn xp_budget(party_levels: &[u32], difficulty: Difficulty) -> u64 {
+    let thresholds = party_thresholds(party_levels);
+    match difficulty {
+        Difficulty::Trivial => 0,
+        Difficulty::Easy => thresholds[0],
+        Difficulty::Medium => thresholds[1],
+        Difficulty::Hard => thresholds[2],
+        Difficulty::Deadly => thresholds[3],
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedMonster {
+    pub name: String,
+    pub challenge_rating: String,
+    pub xp: u64,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncounterPlan {
+    pub monsters: Vec<PlannedMonster>,
+    pub summary: EncounterSummary,
+}
+
+impl EncounterPlan {
+    fn new(monsters: Vec<PlannedMonster>, party_levels: &[u32]) -> Self {
+        let monster_xp: Vec<u64> = monsters
+            .iter()
+            .flat_map(|monster| std::iter::repeat_n(monster.xp, monster.count as usize))
+            .collect();
+        Self {
+            summary: rate_encounter(&monster_xp, party_levels),
+            monsters,
+        }
+    }
+
+    pub fn to_template(&self, name: &str) -> EncounterTemplate {
+        EncounterTemplate {
+            name: name.to_string(),
+            notes: Some(format!(
+                "{} encounter, {} adjusted XP",
+                self.summary.difficulty, self.summary.adjusted_xp
+            )),
+            monsters: self
+                .monsters
+                .iter()
+                .map(|monster| EncounterMonster {
+                    name: monster.name.clone(),
+                    count: monster.count,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for EncounterPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let monsters: Vec<String> = self
+            .monsters
+            .iter()
+            .map(|monster| {
+                format!(
+                    "{}x {} (CR {})",
+                    monster.count, monster.name, monster.challenge_rating
+                )
+            })
+            .collect();
+        write!(
+            f,
+            "{} - {} adjusted XP, {}",
+            monsters.join(" + "),
+            self.summary.adjusted_xp,
+            self.summary.difficulty
+        )
+    }
+}
+
+/// Propose monster mixes of the target difficulty from the candidates: a
+/// number of one monster, or one leader with a number of weaker monsters.
+/// Each mix is proposed once, with the count closest to the budget.
+pub fn suggest_encounters(
+    candidates: &[ApiCreatureSearchItem],
+    party_levels: &[u32],
+    difficulty: Difficulty,
+    limit: usize,
+) -> Vec<EncounterPlan> {
+    let mut monsters: Vec<PlannedMonster> = Vec::new();
+    for candidate in candidates {
+        let Some(cr) = &candidate.challenge_rating else {
+            continue;
+        };
+        let Some(xp) = xp_for(cr) else {
+            continue;
+        };
+        if monsters
+            .iter()
+            .any(|monster| monster.name == candidate.name)
+        {
+            continue;
+        }
+        monsters.push(PlannedMonster {
+            name: candidate.name.clone(),
+            challenge_rating: cr.clone(),
+            xp,
+            count: 1,
+        });
+    }
+
+    let with_count = |monster: &PlannedMonster, count: u32| PlannedMonster {
+        count,
+        ..monster.clone()
+    };
+    let budget = xp_budget(party_levels, difficulty);
+    let closest = |plans: Vec<EncounterPlan>| {
+        plans
+            .into_iter()
+            .filter(|plan| plan.summary.difficulty == difficulty)
+            .min_by_key(|plan| plan.summary.adjusted_xp.abs_diff(budget))
+    };
+    let mut plans = Vec::new();
+    for monster in &monsters {
+        plans.extend(closest(
+            (1..=MAX_COUNT)
+                .map(|count| EncounterPlan::new(vec![with_count(monster, count)], party_levels))
+                .collect(),
+        ));
+
+        for minion in monsters.iter().filter(|minion| minion.xp < monster.xp) {
+            plans.extend(closest(
+                (1..=MAX_COUNT)
+                    .map(|count| {
+                        let mix = vec![with_count(monster, 1), with_count(minion, count)];
+                        EncounterPlan::new(mix, party_levels)
+                    })
+                    .collect(),
+            ));
+        }
+    }
+
+    plans.sort_by_key(|plan| plan.summary.adjusted_xp.abs_diff(budget));
+    plans.truncate(limit);
+    plans
+}
@@ -0,0 +1,328 @@
+use crate::creature::CreatureItem;
+use crate::dice::roll_d20;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ability {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Ability {
+    pub const ALL: [Ability; 6] = [
+        Ability::Strength,
+        Ability::Dexterity,
+        Ability::Constitution,
+        Ability::Intelligence,
+        Ability::Wisdom,
+        Ability::Charisma,
+    ];
+
+    pub fn score(self, creature: &CreatureItem) -> Option<i64> {
+        match self {
+            Ability::Strength => creature.strength,
+            Ability::Dexterity => creature.dexterity,
+            Ability::Constitution => creature.constitution,
+            Ability::Intelligence => creature.intelligence,
+            Ability::Wisdom => creature.wisdom,
+            Ability::Charisma => creature.charisma,
+        }
+    }
+
+    /// The modifier of the ability score, 0 when the score is unknown.
+    pub fn modifier(self, creature: &CreatureItem) -> i64 {
+        self.score(creature).map_or(0, ability_modifier)
+    }
+
+    /// The saving throw bonus from the stat block, falling back to the
+    /// ability modifier.
+    pub fn save_bonus(self, creature: &CreatureItem) -> i64 {
+        let save = match self {
+            Ability::Strength => creature.strength_save,
+            Ability::Dexterity => creature.dexterity_save,
+            Ability::Constitution => creature.constitution_save,
+            Ability::Intelligence => creature.intelligence_save,
+            Ability::Wisdom => creature.wisdom_save,
+            Ability::Charisma => creature.charisma_save,
+        };
+        save.unwrap_or_else(|| self.modifier(creature))
+    }
+}
+
+impl fmt::Display for Ability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let short = match self {
+            Ability::Strength => "STR",
+            Ability::Dexterity => "DEX",
+            Ability::Constitution => "CON",
+            Ability::Intelligence => "INT",
+            Ability::Wisdom => "WIS",
+            Ability::Charisma => "CHA",
+        };
+        write!(f, "{short}")
+    }
+}
+
+impl FromStr for Ability {
+    type Err = String;
+
+    /// Accepts both "dex" and "dexterity".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Ability::ALL
+            .into_iter()
+            .find(|ability| {
+                let name = format!("{ability:?}").to_lowercase();
+                s.len() >= 3 && name.starts_with(&s)
+            })
+            .ok_or_else(|| format!("Unknown ability: {s}"))
+    }
+}
+
+/// The ability modifier of an ability score, e.g. 14 gives +2 and 9 gives -1.
+pub fn ability_modifier(score: i64) -> i64 {
+    (score - 10).div_euclid(2)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Skill {
+    Acrobatics,
+    AnimalHandling,
+    Arcana,
+    Athletics,
+    Deception,
+    History,
+    Insight,
+    Intimidation,
+    Investigation,
+    Medicine,
+    Nature,
+    Perception,
+    Performance,
+    Persuasion,
+    Religion,
+    SleightOfHand,
+    Stealth,
+    Survival,
+}
+
+impl Skill {
+    pub const ALL: [Skill; 18] = [
+        Skill::Acrobatics,
+        Skill::AnimalHandling,
+        Skill::Arcana,
+        Skill::Athletics,
+        Skill::Deception,
+        Skill::History,
+        Skill::Insight,
+        Skill::Intimidation,
+        Skill::Investigation,
+        Skill::Medicine,
+        Skill::Nature,
+        Skill::Perception,
+        Skill::Performance,
+        Skill::Persuasion,
+        Skill::Religion,
+        Skill::SleightOfHand,
+        Skill::Stealth,
+        Skill::Survival,
+    ];
+
+    pub fn ability(self) -> Ability {
+        match self {
+            Skill::Acrobatics => Ability::Dexterity,
+            Skill::AnimalHandling => Ability::Wisdom,
+            Skill::Arcana => Ability::Intelligence,
+            Skill::Athletics => Ability::Strength,
+            Skill::Deception => Ability::Charisma,
+            Skill::History => Ability::Intelligence,
+            Skill::Insight => Ability::Wisdom,
+            Skill::Intimidation => Ability::Charisma,
+            Skill::Investigation => Ability::Intelligence,
+            Skill::Medicine => Ability::Wisdom,
+            Skill::Nature => Ability::Intelligence,
+            Skill::Perception => Ability::Wisdom,
+            Skill::Performance => Ability::Charisma,
+            Skill::Persuasion => Ability::Charisma,
+            Skill::Religion => Ability::Intelligence,
+            Skill::SleightOfHand => Ability::Dexterity,
+            Skill::Stealth => Ability::Dexterity,
+            Skill::Survival => Ability::Wisdom,
+        }
+    }
+
+    /// The skill bonus from the stat block, falling back to the modifier of
+    /// the skill's ability.
+    pub fn bonus(self, creature: &CreatureItem) -> i64 {
+        let bonus = creature.skills.as_ref().and_then(|skills| match self {
+            Skill::Acrobatics => skills.acrobatics,
+            Skill::AnimalHandling => skills.animal_handling,
+            Skill::Arcana => skills.arcana,
+            Skill::Athletics => skills.athletics,
+            Skill::Deception => skills.deception,
+            Skill::History => skills.history,
+            Skill::Insight => skills.insight,
+            Skill::Intimidation => skills.intimidation,
+            Skill::Investigation => skills.investigation,
+            Skill::Medicine => skills.medicine,
+            Skill::Nature => skills.nature,
+            Skill::Perception => skills.perception,
+            Skill::Performance => skills.performance,
+            Skill::Persuasion => skills.persuasion,
+            Skill::Religion => skills.religion,
+            Skill::SleightOfHand => skills.sleight_of_hand,
+            Skill::Stealth => skills.stealth,
+            Skill::Survival => skills.survival,
+        });
+        let bonus = match self {
+            Skill::Perception => bonus.or(creature.perception),
+            _ => bonus,
+        };
+        bonus.unwrap_or_else(|| self.ability().modifier(creature))
+    }
+}
+
+impl fmt::Display for Skill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Skill::Acrobatics => "Acrobatics",
+            Skill::AnimalHandling => "Animal Handling",
+            Skill::Arcana => "Arcana",
+            Skill::Athletics => "Athletics",
+            Skill::Deception => "Deception",
+            Skill::History => "History",
+            Skill::Insight => "Insight",
+            Skill::Intimidation => "Intimidation",
+            Skill::Investigation => "Investigation",
+            Skill::Medicine => "Medicine",
+            Skill::Nature => "Nature",
+            Skill::Perception => "Perception",
+            Skill::Performance => "Performance",
+            Skill::Persuasion => "Persuasion",
+            Skill::Religion => "Religion",
+            Skill::SleightOfHand => "Sleight Of Hand",
+            Skill::Stealth => "Stealth",
+            Skill::Survival => "Survival",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Anything a creature can roll a d20 for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Ability(Ability),
+    Save(Ability),
+    Skill(Skill),
+}
+
+impl Check {
+    /// Ability checks, saving throws and skills, in that order.
+    pub fn all() -> Vec<Check> {
+        let abilities = Ability::ALL.into_iter().map(Check::Ability);
+        let saves = Ability::ALL.into_iter().map(Check::Save);
+        let skills = Skill::ALL.into_iter().map(Check::Skill);
+        abilities.chain(saves).chain(skills).collect()
+    }
+
+    pub fn bonus(self, creature: &CreatureItem) -> i64 {
+        match self {
+            Check::Ability(ability) => ability.modifier(creature),
+            Check::Save(ability) => ability.save_bonus(creature),
+            Check::Skill(skill) => skill.bonus(creature),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Ability(ability) => write!(f, "{ability} check"),
+            Check::Save(ability) => write!(f, "{ability} save"),
+            Check::Skill(skill) => write!(f, "{skill} ({})", skill.ability()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RollMode {
+    #[default]
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+impl RollMode {
+    pub fn next(self) -> Self {
+        match self {
+            RollMode::Normal => RollMode::Advantage,
+            RollMode::Advantage => RollMode::Disadvantage,
+            RollMode::Disadvantage => RollMode::Normal,
+        }
+    }
+}
+
+impl fmt::Display for RollMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RollMode::Normal => write!(f, "Normal"),
+            RollMode::Advantage => write!(f, "Advantage"),
+            RollMode::Disadvantage => write!(f, "Disadvantage"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckRoll {
+    pub check: Check,
+    pub mode: RollMode,
+    pub rolls: Vec<i64>,
+    pub bonus: i64,
+    pub total: i64,
+}
+
+impl CheckRoll {
+    /// Build the result from already rolled d20s, the second one is only
+    /// used with advantage or disadvantage.
+    pub fn from_rolls(check: Check, mode: RollMode, bonus: i64, d20s: (i64, i64)) -> Self {
+        let (rolls, kept) = match mode {
+            RollMode::Normal => (vec![d20s.0], d20s.0),
+            RollMode::Advantage => (vec![d20s.0, d20s.1], d20s.0.max(d20s.1)),
+            RollMode::Disadvantage => (vec![d20s.0, d20s.1], d20s.0.min(d20s.1)),
+        };
+        Self {
+            check,
+            mode,
+            rolls,
+            bonus,
+            total: kept + bonus,
+        }
+    }
+}
+
+impl fmt::Display for CheckRoll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rolls: Vec<String> = self.rolls.iter().map(|r| r.to_string()).collect();
+        write!(
+            f,
+            "{}: [{}] {:+} = {}",
+            self.check,
+            rolls.join(", "),
+            self.bonus,
+            self.total
+        )?;
+        if self.mode != RollMode::Normal {
+            write!(f, " ({})", self.mode)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn roll_check(creature: &CreatureItem, check: Check, mode: RollMode) -> CheckRoll {
+    CheckRoll::from_rolls(check, mode, check.bonus(creature), (roll_d20(), roll_d20()))
+}
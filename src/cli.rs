@@ -0,0 +1,134 @@
+use crate::control::{DEFAULT_CONTROL_PORT, DEFAULT_PLAYERS_PORT};
+use crate::stat_block::StatBlockFormat;
+use std::fmt;
+use std::iter::Peekable;
+
+pub const USAGE: &str = "\
+Usage: wtii [--config <path>] [--load <file>] [--serve [port]] [--players [port]]
+       wtii [--config <path>] search [--format <fmt>] <name>
+       wtii roll <expr>
+
+Options:
+  --config <path>  Use this config file instead of default.yml
+  --load <file>    Open the tracker with a saved encounter
+  --serve [port]   Take commands over HTTP on localhost, port 7878 by default
+  --players [port] Show the player view on localhost, port 7879 by default
+  --format <fmt>   Print stat blocks as text, markdown or json
+  -h, --help       Print this help";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Open the tracker, optionally with a saved encounter.
+    Tui {
+        load: Option<String>,
+        /// Port of the control API on localhost.
+        serve: Option<u16>,
+        /// Port of the read-only player view on localhost.
+        players: Option<u16>,
+    },
+    /// Print the stat blocks of the monsters matching the name.
+    Search {
+        name: String,
+        format: StatBlockFormat,
+    },
+    /// Roll dice, e.g. "2d6+3".
+    Roll {
+        expr: String,
+    },
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cli {
+    pub config: Option<String>,
+    pub command: CliCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n\n{USAGE}", self.0)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Parse the arguments without the program name.
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Cli, CliError> {
+    let mut args = args.into_iter().peekable();
+    let mut config = None;
+    let mut load = None;
+    let mut serve = None;
+    let mut players = None;
+    let mut format = None;
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                return Ok(Cli {
+                    config,
+                    command: CliCommand::Help,
+                })
+            }
+            "--config" => config = Some(value_of(&arg, args.next())?),
+            "--load" => load = Some(value_of(&arg, args.next())?),
+            "--serve" => serve = Some(port_of(&mut args, DEFAULT_CONTROL_PORT)),
+            "--players" => players = Some(port_of(&mut args, DEFAULT_PLAYERS_PORT)),
+            "--format" => {
+                let value = value_of(&arg, args.next())?;
+                format = Some(value.parse::<StatBlockFormat>().map_err(CliError)?);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(CliError(format!("Unknown option: {flag}")))
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = positional.first().map(|arg| arg.as_str());
+    if format.is_some() && command != Some("search") {
+        return Err(CliError("--format only works with search".into()));
+    }
+    let command = match command {
+        None => CliCommand::Tui {
+            load,
+            serve,
+            players,
+        },
+        Some("search") | Some("roll") if load.is_some() || serve.is_some() || players.is_some() => {
+            return Err(CliError(
+                "--load, --serve and --players only work when opening the tracker".into(),
+            ))
+        }
+        Some("search") if positional.len() > 1 => CliCommand::Search {
+            name: positional[1..].join(" "),
+            format: format.unwrap_or_default(),
+        },
+        Some("roll") if positional.len() > 1 => CliCommand::Roll {
+            expr: positional[1..].join(""),
+        },
+        Some(command @ ("search" | "roll")) => {
+            return Err(CliError(format!("Missing argument for {command}")))
+        }
+        Some(command) => return Err(CliError(format!("Unknown command: {command}"))),
+    };
+    Ok(Cli { config, command })
+}
+
+/// The port following the flag, or the default when it is left out.
+fn port_of<I: Iterator<Item = String>>(args: &mut Peekable<I>, default: u16) -> u16 {
+    match args.peek().map(|value| value.parse::<u16>()) {
+        Some(Ok(port)) => {
+            args.next();
+            port
+        }
+        _ => default,
+    }
+}
+
+fn value_of(flag: &str, value: Option<String>) -> Result<String, CliError> {
+    value.ok_or_else(|| CliError(format!("Missing value for {flag}")))
+}
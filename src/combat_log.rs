@@ -0,0 +1,295 @@
+use crate::creature::{get_config_file_location, CreatureItem};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fmt, fs, io};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    TurnStart {
+        creature: String,
+    },
+    Damage {
+        creature: String,
+        amount: u64,
+        source: Option<String>,
+    },
+    Healing {
+        creature: String,
+        amount: u64,
+        source: Option<String>,
+    },
+    Death {
+        creature: String,
+    },
+    ConditionApplied {
+        creature: String,
+        condition: String,
+    },
+    ConditionRemoved {
+        creature: String,
+        condition: String,
+    },
+    Roll {
+        creature: String,
+        result: String,
+    },
+    Revealed {
+        creature: String,
+    },
+    ZoneCreated {
+        creature: String,
+        zone: String,
+    },
+    ZoneEnded {
+        creature: String,
+        zone: String,
+    },
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = |source: &Option<String>| match source {
+            Some(source) => format!(" from {source}"),
+            None => String::new(),
+        };
+        match self {
+            EventKind::TurnStart { creature } => write!(f, "{creature} starts their turn"),
+            EventKind::Damage {
+                creature,
+                amount,
+                source: from,
+            } => write!(f, "{creature} takes {amount} damage{}", source(from)),
+            EventKind::Healing {
+                creature,
+                amount,
+                source: from,
+            } => write!(f, "{creature} heals {amount} HP{}", source(from)),
+            EventKind::Death { creature } => write!(f, "{creature} drops to 0 HP"),
+            EventKind::ConditionApplied {
+                creature,
+                condition,
+            } => write!(f, "{creature} is {condition}"),
+            EventKind::ConditionRemoved {
+                creature,
+                condition,
+            } => write!(f, "{creature} is no longer {condition}"),
+            EventKind::Roll { creature, result } => write!(f, "{creature} rolls {result}"),
+            EventKind::Revealed { creature } => write!(f, "{creature} appears"),
+            EventKind::ZoneCreated { creature, zone } => write!(f, "{creature} creates {zone}"),
+            EventKind::ZoneEnded { creature, zone } => write!(f, "{zone} of {creature} ends"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogEvent {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub round: u32,
+    #[serde(flatten)]
+    pub kind: EventKind,
+    /// The creature whose HP changed, to merge manual HP changes.
+    #[serde(skip)]
+    creature_id: Option<u64>,
+    /// About a hidden creature, only the DM sees it and it is not exported.
+    #[serde(skip)]
+    pub hidden: bool,
+}
+
+impl LogEvent {
+    /// The time of day in UTC, e.g. "19:04:55".
+    pub fn time(&self) -> String {
+        let seconds = self.timestamp % 86400;
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    }
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] ", self.time())?;
+        if self.round > 0 {
+            write!(f, "Round {}: ", self.round)?;
+        }
+        write!(f, "{}", self.kind)?;
+        if self.hidden {
+            write!(f, " (hidden)")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Markdown => write!(f, "Markdown"),
+            ExportFormat::Json => write!(f, "JSON"),
+        }
+    }
+}
+
+/// Everything that happened during the encounter, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct CombatLog {
+    events: Vec<LogEvent>,
+}
+
+impl CombatLog {
+    pub fn events(&self) -> &[LogEvent] {
+        &self.events
+    }
+
+    pub fn log(&mut self, round: u32, kind: EventKind) {
+        self.push(now(), round, kind, None);
+    }
+
+    /// Log an event about the creature, kept out of exports while it is hidden.
+    pub fn log_creature(&mut self, round: u32, creature: &CreatureItem, kind: EventKind) {
+        self.events.push(LogEvent {
+            timestamp: now(),
+            round,
+            kind,
+            creature_id: Some(creature.id),
+            hidden: creature.hidden,
+        });
+    }
+
+    pub fn push(&mut self, timestamp: u64, round: u32, kind: EventKind, creature_id: Option<u64>) {
+        self.events.push(LogEvent {
+            timestamp,
+            round,
+            kind,
+            creature_id,
+            hidden: false,
+        });
+    }
+
+    /// The events the players may see.
+    pub fn public_events(&self) -> impl Iterator<Item = &LogEvent> {
+        self.events.iter().filter(|event| !event.hidden)
+    }
+
+    /// Log a change of HP, positive for healing. Consecutive changes of the
+    /// same creature without a source are one event, as HP is changed one
+    /// point at a time.
+    pub fn log_hit_points(
+        &mut self,
+        round: u32,
+        creature: &CreatureItem,
+        change: i64,
+        source: Option<String>,
+    ) {
+        let mut change = change;
+        if source.is_none() {
+            if let Some(last) = self.events.last() {
+                let last_change = match &last.kind {
+                    EventKind::Damage {
+                        amount,
+                        source: None,
+                        ..
+                    } => Some(-(*amount as i64)),
+                    EventKind::Healing {
+                        amount,
+                        source: None,
+                        ..
+                    } => Some(*amount as i64),
+                    _ => None,
+                };
+                if let Some(last_change) =
+                    last_change.filter(|_| last.creature_id == Some(creature.id))
+                {
+                    change += last_change;
+                    self.events.pop();
+                }
+            }
+        }
+
+        let name = creature.name.clone();
+        let kind = match change {
+            0 => return,
+            change if change < 0 => EventKind::Damage {
+                creature: name,
+                amount: change.unsigned_abs(),
+                source,
+            },
+            change => EventKind::Healing {
+                creature: name,
+                amount: change as u64,
+                source,
+            },
+        };
+        self.log_creature(round, creature, kind);
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Combat Log\n");
+        let mut round = None;
+        for event in self.public_events() {
+            if round != Some(event.round) {
+                round = Some(event.round);
+                if event.round == 0 {
+                    markdown.push_str("\n## Before combat\n\n");
+                } else {
+                    markdown.push_str(&format!("\n## Round {}\n\n", event.round));
+                }
+            }
+            markdown.push_str(&format!("- {} {}\n", event.time(), event.kind));
+        }
+        markdown
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.public_events().collect::<Vec<_>>())
+    }
+
+    /// Write the log to a new file in the directory, named after the time of
+    /// the first event.
+    pub fn export(&self, dir: &Path, format: ExportFormat) -> io::Result<PathBuf> {
+        let contents = match format {
+            ExportFormat::Markdown => self.to_markdown(),
+            ExportFormat::Json => self.to_json().map_err(io::Error::other)?,
+        };
+        let started = self
+            .events
+            .first()
+            .map_or_else(now, |event| event.timestamp);
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("combat-log-{started}.{}", format.extension()));
+        fs::write(&path, contents)?;
+        Ok(path)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Exported logs are kept in a `logs` directory next to `default.yml`.
+pub fn get_logs_dir() -> Option<PathBuf> {
+    let config_path = get_config_file_location()?;
+    Some(Path::new(&config_path).parent()?.join("logs"))
+}
@@ -0,0 +1,421 @@
+use crate::creature::{CreatureItem, CreatureList, Faction, Status};
+use crate::dice::roll_d20;
+use crate::health::HealthConfig;
+use crate::player_view::PlayerView;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+
+pub const DEFAULT_CONTROL_PORT: u16 = 7878;
+pub const DEFAULT_PLAYERS_PORT: u16 = 7879;
+const MAX_BODY_LENGTH: usize = 64 * 1024;
+
+/// A creature by its id, or by its name when several share it the first one.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Target {
+    Id(u64),
+    Name(String),
+}
+
+impl Target {
+    pub fn position(&self, list: &CreatureList) -> Option<usize> {
+        match self {
+            Target::Id(id) => list.position(*id),
+            Target::Name(name) => list
+                .items
+                .iter()
+                .position(|creature| creature.name.eq_ignore_ascii_case(name)),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Id(id) => write!(f, "id {id}"),
+            Target::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// Damage or healing, for the creature whose turn it is without a target.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HitPointsChange {
+    #[serde(default)]
+    pub target: Option<Target>,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NewCreature {
+    pub name: String,
+    #[serde(default)]
+    pub player: bool,
+    pub hit_points: Option<u64>,
+    pub armor_class: Option<i64>,
+    /// Rolled for NPCs when it is left out.
+    pub initiative: Option<i64>,
+}
+
+impl NewCreature {
+    pub fn to_creature(&self) -> CreatureItem {
+        let mut creature = CreatureItem::new_player(&self.name, None);
+        if !self.player {
+            creature.faction = Faction::Npc;
+            creature.untracked_hp = false;
+            creature.initiative = Some(roll_d20());
+        }
+        if let Some(hit_points) = self.hit_points {
+            creature.set_max_hit_points(hit_points);
+        }
+        if self.initiative.is_some() {
+            creature.initiative = self.initiative;
+        }
+        creature.armor_class = self.armor_class;
+        creature
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlCommand {
+    State,
+    NextTurn,
+    Damage(HitPointsChange),
+    Heal(HitPointsChange),
+    AddCreature(NewCreature),
+}
+
+/// A command for the running tracker, answered with the encounter after it
+/// has been applied or with why it could not be.
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    pub reply: oneshot::Sender<Result<EncounterState, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreatureState {
+    pub id: u64,
+    pub name: String,
+    pub player: bool,
+    pub initiative: Option<i64>,
+    pub hit_points: u64,
+    pub max_hit_points: u64,
+    pub armor_class: Option<i64>,
+    /// The HP of a player the DM doesn't track are left to the player.
+    pub untracked_hp: bool,
+    /// How hurt it looks, e.g. "Bloodied", by the health thresholds.
+    pub descriptor: String,
+    pub dead: bool,
+    pub hidden: bool,
+    pub conditions: Vec<String>,
+}
+
+impl CreatureState {
+    pub fn new(creature: &CreatureItem, health: &HealthConfig) -> Self {
+        CreatureState {
+            id: creature.id,
+            name: creature.name.clone(),
+            player: creature.faction == Faction::Player,
+            initiative: creature.initiative,
+            hit_points: creature.hit_points,
+            max_hit_points: creature.max_hit_points,
+            armor_class: creature.armor_class,
+            untracked_hp: creature.untracked_hp,
+            descriptor: health.descriptor(creature),
+            dead: creature.status == Status::Dead,
+            hidden: creature.hidden,
+            conditions: creature.conditions.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncounterState {
+    pub round: u32,
+    /// The id of the creature whose turn it is.
+    pub current: Option<u64>,
+    pub creatures: Vec<CreatureState>,
+}
+
+impl EncounterState {
+    /// `current` is the index of the creature whose turn it is, which is not
+    /// the selected one while peeking at another creature.
+    pub fn new(list: &CreatureList, current: Option<usize>) -> Self {
+        EncounterState {
+            round: list.round,
+            current: current
+                .and_then(|i| list.items.get(i))
+                .map(|creature| creature.id),
+            creatures: list
+                .items
+                .iter()
+                .map(|creature| CreatureState::new(creature, &list.health))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlError {
+    NotFound,
+    BadRequest(String),
+    Forbidden(String),
+    UnsupportedMediaType,
+    Unavailable,
+}
+
+impl ControlError {
+    fn status(&self) -> &'static str {
+        match self {
+            ControlError::NotFound => "404 Not Found",
+            ControlError::BadRequest(_) => "400 Bad Request",
+            ControlError::Forbidden(_) => "403 Forbidden",
+            ControlError::UnsupportedMediaType => "415 Unsupported Media Type",
+            ControlError::Unavailable => "503 Service Unavailable",
+        }
+    }
+}
+
+impl fmt::Display for ControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlError::NotFound => write!(f, "Not found"),
+            ControlError::BadRequest(e) | ControlError::Forbidden(e) => write!(f, "{e}"),
+            ControlError::UnsupportedMediaType => {
+                write!(f, "The content type must be application/json")
+            }
+            ControlError::Unavailable => write!(f, "The tracker is not running"),
+        }
+    }
+}
+
+/// The command of an HTTP request:
+///
+/// - `GET /state`
+/// - `POST /next-turn`
+/// - `POST /damage` and `POST /heal` with `{"target": 3, "amount": 7}`
+/// - `POST /creatures` with `{"name": "Goblin", "hit_points": 7}`
+pub fn route(method: &str, path: &str, body: &str) -> Result<ControlCommand, ControlError> {
+    let parse_error = |e: serde_json::Error| ControlError::BadRequest(e.to_string());
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/state") => Ok(ControlCommand::State),
+        ("POST", "/next-turn") => Ok(ControlCommand::NextTurn),
+        ("POST", "/damage") => Ok(ControlCommand::Damage(
+            serde_json::from_str(body).map_err(parse_error)?,
+        )),
+        ("POST", "/heal") => Ok(ControlCommand::Heal(
+            serde_json::from_str(body).map_err(parse_error)?,
+        )),
+        ("POST", "/creatures") => Ok(ControlCommand::AddCreature(
+            serde_json::from_str(body).map_err(parse_error)?,
+        )),
+        _ => Err(ControlError::NotFound),
+    }
+}
+
+/// The player view only reads the encounter, it answers `GET /players` and
+/// `GET /players.json`, see [`PlayerView`].
+pub fn route_players(
+    method: &str,
+    path: &str,
+    _body: &str,
+) -> Result<ControlCommand, ControlError> {
+    match (method, path.trim_end_matches('/')) {
+        ("GET", "/players" | "/players.json") => Ok(ControlCommand::State),
+        _ => Err(ControlError::NotFound),
+    }
+}
+
+/// The headers of a request that decide whether it is answered.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestHeaders {
+    pub host: Option<String>,
+    pub origin: Option<String>,
+    pub content_type: Option<String>,
+    pub content_length: usize,
+}
+
+impl RequestHeaders {
+    /// Only requests for localhost are answered, so a web page can't reach
+    /// the tracker by pointing its own domain at 127.0.0.1, and browsers may
+    /// only send them from the tracker's own pages on `port`.
+    pub fn check_origin(&self, port: u16) -> Result<(), ControlError> {
+        let host = self.host.as_deref().unwrap_or_default();
+        let hostname = match host.rsplit_once(':') {
+            Some((hostname, port)) if port.chars().all(|c| c.is_ascii_digit()) => hostname,
+            _ => host,
+        };
+        if !is_localhost(hostname) {
+            return Err(ControlError::Forbidden(format!(
+                "The host must be localhost, not {host:?}"
+            )));
+        }
+        if let Some(origin) = &self.origin {
+            let allowed = origin
+                .strip_prefix("http://")
+                .and_then(|origin| origin.rsplit_once(':'))
+                .is_some_and(|(hostname, origin_port)| {
+                    is_localhost(hostname) && origin_port == port.to_string()
+                });
+            if !allowed {
+                return Err(ControlError::Forbidden(format!(
+                    "Requests from {origin} are not allowed"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Commands take JSON, which a browser only sends to another site after
+    /// asking first, and the tracker never says yes.
+    pub fn check_content_type(&self, method: &str) -> Result<(), ControlError> {
+        if method == "GET" && self.content_length == 0 {
+            return Ok(());
+        }
+        let media_type = self
+            .content_type
+            .as_deref()
+            .and_then(|content_type| content_type.split(';').next())
+            .unwrap_or_default();
+        if media_type.trim().eq_ignore_ascii_case("application/json") {
+            Ok(())
+        } else {
+            Err(ControlError::UnsupportedMediaType)
+        }
+    }
+}
+
+fn is_localhost(hostname: &str) -> bool {
+    hostname.eq_ignore_ascii_case("localhost") || hostname == "127.0.0.1"
+}
+
+type Route = fn(&str, &str, &str) -> Result<ControlCommand, ControlError>;
+
+/// The content type and body answering a request for the path.
+pub fn response_body(path: &str, state: &EncounterState) -> (&'static str, String) {
+    match path.trim_end_matches('/') {
+        "/players" => ("text/html; charset=utf-8", PlayerView::new(state).to_html()),
+        "/players.json" => (
+            "application/json",
+            serde_json::to_string(&PlayerView::new(state)).unwrap_or_default(),
+        ),
+        _ => (
+            "application/json",
+            serde_json::to_string(state).unwrap_or_default(),
+        ),
+    }
+}
+
+/// Accept connections until the listener fails, passing the commands on to
+/// the tracker.
+pub async fn serve(
+    listener: TcpListener,
+    tx: mpsc::UnboundedSender<ControlRequest>,
+) -> io::Result<()> {
+    accept(listener, tx, route).await
+}
+
+/// Accept connections for the player view, which is kept apart from the
+/// control API so the players' screen can't change the encounter.
+pub async fn serve_players(
+    listener: TcpListener,
+    tx: mpsc::UnboundedSender<ControlRequest>,
+) -> io::Result<()> {
+    accept(listener, tx, route_players).await
+}
+
+async fn accept(
+    listener: TcpListener,
+    tx: mpsc::UnboundedSender<ControlRequest>,
+    route: Route,
+) -> io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, tx, route).await;
+        });
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    tx: mpsc::UnboundedSender<ControlRequest>,
+    route: Route,
+) -> io::Result<()> {
+    let port = stream.local_addr()?.port();
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = RequestHeaders::default();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = value.trim().to_string();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => headers.content_length = value.parse().unwrap_or(0),
+                "content-type" => headers.content_type = Some(value),
+                "host" => headers.host = Some(value),
+                "origin" => headers.origin = Some(value),
+                _ => {}
+            }
+        }
+    }
+
+    let result = if headers.content_length > MAX_BODY_LENGTH {
+        Err(ControlError::BadRequest("Body too large".to_string()))
+    } else if let Err(e) = headers.check_origin(port) {
+        Err(e)
+    } else {
+        let mut body = vec![0; headers.content_length];
+        reader.read_exact(&mut body).await?;
+        let body = String::from_utf8_lossy(&body);
+        let command = route(&method, &path, &body)
+            .and_then(|command| headers.check_content_type(&method).map(|()| command));
+        match command {
+            Ok(command) => send(&tx, command).await,
+            Err(e) => Err(e),
+        }
+    };
+
+    let (status, (content_type, body)) = match result {
+        Ok(state) => ("200 OK", response_body(&path, &state)),
+        Err(e) => (
+            e.status(),
+            (
+                "application/json",
+                serde_json::json!({ "error": e.to_string() }).to_string(),
+            ),
+        ),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let mut stream = reader.into_inner();
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn send(
+    tx: &mpsc::UnboundedSender<ControlRequest>,
+    command: ControlCommand,
+) -> Result<EncounterState, ControlError> {
+    let (reply, rx) = oneshot::channel();
+    tx.send(ControlRequest { command, reply })
+        .map_err(|_| ControlError::Unavailable)?;
+    rx.await
+        .map_err(|_| ControlError::Unavailable)?
+        .map_err(ControlError::BadRequest)
+}
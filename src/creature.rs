@@ -1,21 +1,41 @@
+use crate::abilities::{parse_limited_uses, LimitedUse, Rest};
+use crate::checks::ability_modifier;
+use crate::dice::DiceExpr;
+use crate::health::{hp_gauge, HealthConfig, GAUGE_WIDTH};
+use crate::ongoing::OngoingEffect;
+use crate::spells::{parse_spell_slots, ApiSpell, SpellSlot};
+use crate::zone::ZoneEffect;
 use rand::random_range;
 use ratatui::prelude::Color;
-use ratatui::style::palette::tailwind::{GREEN, RED, YELLOW};
+use ratatui::style::palette::tailwind::{GREEN, PURPLE, RED, YELLOW};
 use ratatui::{
-    text::Line,
+    text::{Line, Span},
     widgets::{ListItem, ListState},
 };
 use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
 use std::{fmt, fs};
-use yaml_rust2::YamlLoader;
+use yaml_rust2::{Yaml, YamlLoader};
 
 const COMPLETED_TEXT_FG_COLOR: Color = GREEN.c500;
 const DEAD_TEXT_FG_COLOR: Color = RED.c500;
 const NO_INITIATIVE_STYLE: Color = YELLOW.c300;
+const FLEE_TEXT_FG_COLOR: Color = PURPLE.c400;
 
 pub struct CreatureList {
     pub items: Vec<CreatureItem>,
     pub state: ListState,
+    /// The current round, 0 while the encounter is being set up.
+    pub round: u32,
+    /// NPCs sharing a name share one initiative slot.
+    pub group_initiative: bool,
+    pub collapsed_groups: HashSet<String>,
+    /// How hurt creatures are shown, from `default.yml`.
+    pub health: HealthConfig,
+    /// Effects on the battlefield, like Spirit Guardians.
+    pub zones: Vec<ZoneEffect>,
+    next_id: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -57,25 +77,61 @@ impl fmt::Display for Speed {
     }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct Skills {
+    pub acrobatics: Option<i64>,
+    pub animal_handling: Option<i64>,
+    pub arcana: Option<i64>,
     pub athletics: Option<i64>,
+    pub deception: Option<i64>,
+    pub history: Option<i64>,
+    pub insight: Option<i64>,
+    pub intimidation: Option<i64>,
+    pub investigation: Option<i64>,
+    pub medicine: Option<i64>,
+    pub nature: Option<i64>,
     pub perception: Option<i64>,
+    pub performance: Option<i64>,
+    pub persuasion: Option<i64>,
+    pub religion: Option<i64>,
+    pub sleight_of_hand: Option<i64>,
     pub stealth: Option<i64>,
+    pub survival: Option<i64>,
+}
+
+impl Skills {
+    /// Every skill by name, in alphabetical order.
+    pub fn entries(&self) -> [(&'static str, Option<i64>); 18] {
+        [
+            ("Acrobatics", self.acrobatics),
+            ("Animal Handling", self.animal_handling),
+            ("Arcana", self.arcana),
+            ("Athletics", self.athletics),
+            ("Deception", self.deception),
+            ("History", self.history),
+            ("Insight", self.insight),
+            ("Intimidation", self.intimidation),
+            ("Investigation", self.investigation),
+            ("Medicine", self.medicine),
+            ("Nature", self.nature),
+            ("Perception", self.perception),
+            ("Performance", self.performance),
+            ("Persuasion", self.persuasion),
+            ("Religion", self.religion),
+            ("Sleight Of Hand", self.sleight_of_hand),
+            ("Stealth", self.stealth),
+            ("Survival", self.survival),
+        ]
+    }
 }
 
 impl fmt::Display for Skills {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut parts = Vec::new();
-        if let Some(val) = self.athletics {
-            parts.push(format!("Athletics: {val}"));
-        }
-        if let Some(val) = self.perception {
-            parts.push(format!("Perception: {val}"));
-        }
-        if let Some(val) = self.stealth {
-            parts.push(format!("Stealth: {val}"));
-        }
+        let parts: Vec<String> = self
+            .entries()
+            .iter()
+            .filter_map(|(name, val)| val.map(|val| format!("{name}: {val}")))
+            .collect();
         write!(f, "{}", parts.join(", "))
     }
 }
@@ -86,6 +142,8 @@ pub struct Action {
     pub desc: String,
     pub attack_bonus: Option<i64>,
     pub damage_dice: Option<String>,
+    #[serde(skip)]
+    pub uses: Vec<LimitedUse>,
 }
 
 impl fmt::Display for Action {
@@ -98,7 +156,8 @@ impl fmt::Display for Action {
         if let Some(val) = self.damage_dice.clone() {
             parts.push(format!("(Damage Dice: {val})"));
         }
-        write!(f, "{}", parts.join(", "))
+        write!(f, "{}", parts.join(", "))?;
+        write_limited_uses(f, &self.uses)
     }
 }
 
@@ -106,12 +165,23 @@ impl fmt::Display for Action {
 pub struct SpecialAbility {
     pub name: String,
     pub desc: String,
+    #[serde(skip)]
+    pub uses: Vec<LimitedUse>,
 }
 
 impl fmt::Display for SpecialAbility {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.name, self.desc)
+        write!(f, "{}: {}", self.name, self.desc)?;
+        write_limited_uses(f, &self.uses)
+    }
+}
+
+fn write_limited_uses(f: &mut fmt::Formatter<'_>, uses: &[LimitedUse]) -> fmt::Result {
+    if uses.is_empty() {
+        return Ok(());
     }
+    let uses: Vec<String> = uses.iter().map(|u| u.to_string()).collect();
+    write!(f, "\nUses left: {}", uses.join(", "))
 }
 
 #[derive(Debug, Deserialize)]
@@ -119,6 +189,8 @@ pub struct ApiCreatureSearchItem {
     pub name: String,
     pub desc: Option<String>,
     pub size: Option<String>,
+    #[serde(rename = "type")]
+    pub creature_type: Option<String>,
     pub subtype: Option<String>,
     pub group: Option<String>,
     pub alignment: Option<String>,
@@ -198,6 +270,66 @@ pub struct CreatureItem {
     pub legendary_actions: Option<Vec<Action>>,
     pub reactions: Option<Vec<Reaction>>,
     pub special_abilities: Option<Vec<SpecialAbility>>,
+    #[serde(skip)]
+    pub spell_slots: Vec<SpellSlot>,
+    pub spell_list: Option<Vec<String>>,
+    #[serde(skip)]
+    pub spells: Vec<ApiSpell>,
+    #[serde(default)]
+    pub conditions: Vec<String>,
+    #[serde(default)]
+    pub economy: ActionEconomy,
+    #[serde(default)]
+    pub delayed: bool,
+    pub readied: Option<String>,
+    #[serde(default)]
+    pub surprised: bool,
+    #[serde(skip)]
+    pub marked: bool,
+    /// Takes part in initiative but is left out of what the players see.
+    #[serde(default)]
+    pub hidden: bool,
+    /// A player without `hit_points` in `default.yml`, whose HP is left to
+    /// the player.
+    #[serde(default)]
+    pub untracked_hp: bool,
+    #[serde(skip)]
+    pub effects: Vec<OngoingEffect>,
+    /// Character level of a player, used for the encounter difficulty.
+    pub level: Option<u32>,
+    /// Identifies the creature in the history, 0 until it is in a list.
+    #[serde(skip)]
+    pub id: u64,
+}
+
+/// What a creature has already spent since the start of its turn.
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ActionEconomy {
+    pub action: bool,
+    pub bonus_action: bool,
+    pub reaction: bool,
+}
+
+impl ActionEconomy {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl fmt::Display for ActionEconomy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.action {
+            parts.push("A");
+        }
+        if self.bonus_action {
+            parts.push("B");
+        }
+        if self.reaction {
+            parts.push("R");
+        }
+        write!(f, "{}", parts.join(""))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -260,16 +392,31 @@ impl CreatureItem {
             legendary_actions: None,
             reactions: None,
             special_abilities: None,
+            spell_slots: Vec::new(),
+            spell_list: None,
+            spells: Vec::new(),
+            conditions: Vec::new(),
+            economy: ActionEconomy::default(),
+            delayed: false,
+            readied: None,
+            surprised: false,
+            marked: false,
+            hidden: false,
+            untracked_hp: true,
+            effects: Vec::new(),
+            level: None,
+            id: 0,
         }
     }
 
     pub fn new_npc(api_creature: &ApiCreatureSearchItem) -> Self {
-        Self {
+        let mut creature = Self {
             status: Status::Alive,
             faction: Faction::Npc,
             name: api_creature.name.clone(),
             initiative: {
-                let initiative_modifier: i64 = (api_creature.dexterity.unwrap_or(0) - 10) / 2;
+                let initiative_modifier: i64 =
+                    ability_modifier(api_creature.dexterity.unwrap_or(0));
                 Some(random_range(1..21) + initiative_modifier)
             },
             max_hit_points: api_creature.hit_points.unwrap(),
@@ -305,12 +452,405 @@ impl CreatureItem {
             legendary_actions: api_creature.legendary_actions.clone(),
             reactions: api_creature.reactions.clone(),
             special_abilities: api_creature.special_abilities.clone(),
+            spell_slots: api_creature
+                .special_abilities
+                .iter()
+                .flatten()
+                .flat_map(|ability| parse_spell_slots(&ability.desc))
+                .collect(),
+            spell_list: api_creature.spell_list.clone(),
+            spells: Vec::new(),
+            conditions: Vec::new(),
+            economy: ActionEconomy::default(),
+            delayed: false,
+            readied: None,
+            surprised: false,
+            marked: false,
+            hidden: false,
+            untracked_hp: false,
+            effects: Vec::new(),
+            level: None,
+            id: 0,
+        };
+        creature.track_limited_uses();
+        creature
+    }
+
+    fn track_limited_uses(&mut self) {
+        let actions = self
+            .actions
+            .iter_mut()
+            .chain(self.legendary_actions.iter_mut())
+            .flatten();
+        for action in actions {
+            action.uses = parse_limited_uses(&action.name, &action.desc);
         }
+        for ability in self.special_abilities.iter_mut().flatten() {
+            ability.uses = parse_limited_uses(&ability.name, &ability.desc);
+        }
+    }
+
+    pub fn limited_uses(&self) -> Vec<&LimitedUse> {
+        let actions = self
+            .actions
+            .iter()
+            .chain(self.legendary_actions.iter())
+            .flatten()
+            .flat_map(|action| action.uses.iter());
+        let abilities = self
+            .special_abilities
+            .iter()
+            .flatten()
+            .flat_map(|ability| ability.uses.iter());
+        abilities.chain(actions).collect()
+    }
+
+    pub fn limited_uses_mut(&mut self) -> Vec<&mut LimitedUse> {
+        let actions = self
+            .actions
+            .iter_mut()
+            .chain(self.legendary_actions.iter_mut())
+            .flatten()
+            .flat_map(|action| action.uses.iter_mut());
+        let abilities = self
+            .special_abilities
+            .iter_mut()
+            .flatten()
+            .flat_map(|ability| ability.uses.iter_mut());
+        abilities.chain(actions).collect()
+    }
+
+    pub fn rest(&mut self, rest: Rest) {
+        for limited_use in self.limited_uses_mut() {
+            if limited_use.recovers_on(rest) {
+                limited_use.reset();
+            }
+        }
+        for spell_slot in self.spell_slots.iter_mut() {
+            spell_slot.rest(rest);
+        }
+    }
+
+    /// Start tracking the HP of the creature, at full health.
+    pub fn set_max_hit_points(&mut self, hit_points: u64) {
+        self.max_hit_points = hit_points;
+        self.hit_points = hit_points;
+        self.untracked_hp = false;
+    }
+
+    /// Does nothing when the HP is not tracked.
+    pub fn take_damage(&mut self, damage: u64) {
+        if self.untracked_hp {
+            return;
+        }
+        self.hit_points = self.hit_points.saturating_sub(damage);
+        if self.hit_points == 0 {
+            self.status = Status::Dead;
+        }
+    }
+
+    /// Does nothing when the HP is not tracked.
+    pub fn heal(&mut self, amount: u64) {
+        if self.untracked_hp {
+            return;
+        }
+        self.hit_points = (self.hit_points + amount).min(self.max_hit_points);
+        if self.hit_points > 0 {
+            self.status = Status::Alive;
+        }
+    }
+
+    /// Roll the hit dice for the hit points instead of using the average.
+    pub fn roll_hit_points(&mut self) {
+        let Some(hit_dice) = self
+            .hit_dice
+            .as_ref()
+            .and_then(|d| d.parse::<DiceExpr>().ok())
+        else {
+            return;
+        };
+        let hit_points = hit_dice.roll().total.max(1) as u64;
+        self.max_hit_points = hit_points;
+        self.hit_points = hit_points;
+    }
+
+    pub fn attacks(&self) -> Vec<&Action> {
+        self.actions
+            .iter()
+            .flatten()
+            .filter(|action| action.attack_bonus.is_some())
+            .collect()
+    }
+
+    /// Called when the turn order reaches the creature.
+    pub fn start_turn(&mut self) {
+        self.economy.reset();
+        self.readied = None;
+    }
+
+    /// Apply the condition, or remove it if it is already applied.
+    pub fn toggle_condition(&mut self, name: &str) {
+        match self
+            .conditions
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(name))
+        {
+            Some(i) => {
+                self.conditions.remove(i);
+            }
+            None => self.conditions.push(name.to_string()),
+        }
+    }
+
+    pub fn has_condition(&self, name: &str) -> bool {
+        self.conditions.iter().any(|c| c.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_spellcaster(&self) -> bool {
+        !self.spell_slots.is_empty() || self.spell_list.as_ref().is_some_and(|l| !l.is_empty())
     }
 }
 
 impl CreatureList {
-    pub fn add_new_creature(&mut self, creature_item: CreatureItem) {
+    pub fn new(items: Vec<CreatureItem>) -> Self {
+        let mut creature_list = Self {
+            items: Vec::new(),
+            state: ListState::default(),
+            round: 0,
+            group_initiative: false,
+            collapsed_groups: HashSet::new(),
+            health: HealthConfig::default(),
+            zones: Vec::new(),
+            next_id: 1,
+        };
+        for mut creature in items {
+            creature.id = creature_list.next_id();
+            creature_list.items.push(creature);
+        }
+        creature_list
+    }
+
+    /// Number the creatures after those of `other`, so the ids stay unique
+    /// when this list replaces it and the history can tell them apart.
+    pub fn renumber_after(&mut self, other: &CreatureList) {
+        self.next_id = other.next_id;
+        for i in 0..self.items.len() {
+            self.items[i].id = self.next_id();
+        }
+    }
+
+    /// A new id for a creature joining the list.
+    pub fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.items.iter().position(|creature| creature.id == id)
+    }
+
+    pub fn in_combat(&self) -> bool {
+        self.round > 0
+    }
+
+    /// Start the first round. Fails with the names of the living creatures
+    /// that have no initiative yet.
+    pub fn start_combat(&mut self) -> Result<(), Vec<String>> {
+        let missing: Vec<String> = self
+            .items
+            .iter()
+            .filter(|c| c.status == Status::Alive && c.initiative.is_none())
+            .map(|c| c.name.clone())
+            .collect();
+        if !missing.is_empty() {
+            return Err(missing);
+        }
+        self.sort_creature_list();
+        self.round = 1;
+        Ok(())
+    }
+
+    pub fn next_round(&mut self) {
+        if !self.in_combat() {
+            return;
+        }
+        self.round += 1;
+        for creature in self.items.iter_mut() {
+            creature.surprised = false;
+        }
+    }
+
+    /// Whether the turn order passes over the creature.
+    pub fn skips_turn(&self, i: usize) -> bool {
+        let creature = &self.items[i];
+        creature.delayed || (creature.surprised && self.round == 1) || self.is_folded(i)
+    }
+
+    /// The group the creature belongs to, if group initiative is used and
+    /// there are other NPCs with the same name.
+    pub fn group_of(&self, i: usize) -> Option<&str> {
+        if !self.group_initiative {
+            return None;
+        }
+        let creature = self.items.get(i)?;
+        if creature.faction != Faction::Npc {
+            return None;
+        }
+        let members = self
+            .items
+            .iter()
+            .filter(|c| c.faction == Faction::Npc && c.name == creature.name)
+            .count();
+        if members > 1 {
+            Some(&creature.name)
+        } else {
+            None
+        }
+    }
+
+    pub fn group_members(&self, i: usize) -> Vec<usize> {
+        match self.group_of(i) {
+            Some(name) => self
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.faction == Faction::Npc && c.name == name)
+                .map(|(j, _)| j)
+                .collect(),
+            None => vec![i],
+        }
+    }
+
+    /// Whether the creature is hidden inside a collapsed group, the first
+    /// member of a group stands in for the whole group.
+    pub fn is_folded(&self, i: usize) -> bool {
+        match self.group_of(i) {
+            Some(name) => {
+                self.collapsed_groups.contains(name) && i > 0 && self.group_of(i - 1) == Some(name)
+            }
+            None => false,
+        }
+    }
+
+    /// The creatures acting on the turn of the one at `i`, the first member of
+    /// a collapsed group acts for the whole group.
+    pub fn turn_members(&self, i: usize) -> Vec<usize> {
+        self.group_members(i)
+            .into_iter()
+            .filter(|&j| j == i || self.is_folded(j))
+            .collect()
+    }
+
+    /// The marked creatures, or the selected one if nothing is marked.
+    pub fn targets(&self) -> Vec<usize> {
+        let marked: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.marked)
+            .map(|(i, _)| i)
+            .collect();
+        if marked.is_empty() {
+            self.state.selected().into_iter().collect()
+        } else {
+            marked
+        }
+    }
+
+    pub fn unmark_all(&mut self) {
+        for creature in self.items.iter_mut() {
+            creature.marked = false;
+        }
+    }
+
+    /// The living NPCs with the same name as the creature, attacking as a mob.
+    pub fn mob_members(&self, i: usize) -> Vec<usize> {
+        let Some(creature) = self.items.get(i) else {
+            return Vec::new();
+        };
+        if creature.faction != Faction::Npc {
+            return Vec::new();
+        }
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                c.faction == Faction::Npc && c.status == Status::Alive && c.name == creature.name
+            })
+            .map(|(j, _)| j)
+            .collect()
+    }
+
+    pub fn toggle_group_initiative(&mut self) {
+        self.group_initiative = !self.group_initiative;
+        if self.group_initiative {
+            self.roll_group_initiative();
+        } else {
+            self.collapsed_groups.clear();
+        }
+        self.sort_creature_list();
+    }
+
+    /// Roll one initiative for every group and give it to all its members.
+    pub fn roll_group_initiative(&mut self) {
+        let mut rolled = HashSet::new();
+        for i in 0..self.items.len() {
+            let Some(name) = self.group_of(i).map(|n| n.to_string()) else {
+                continue;
+            };
+            if !rolled.insert(name) {
+                continue;
+            }
+            let initiative_modifier: i64 = ability_modifier(self.items[i].dexterity.unwrap_or(0));
+            let initiative = Some(random_range(1..21) + initiative_modifier);
+            for j in self.group_members(i) {
+                self.items[j].initiative = initiative;
+            }
+        }
+    }
+
+    pub fn toggle_collapsed(&mut self, i: usize) {
+        let Some(name) = self.group_of(i).map(|n| n.to_string()) else {
+            return;
+        };
+        if !self.collapsed_groups.remove(&name) {
+            self.collapsed_groups.insert(name);
+        }
+    }
+
+    /// Set the initiative of the creature, or of its whole group.
+    pub fn set_initiative(&mut self, i: usize, initiative: Option<i64>) {
+        for j in self.group_members(i) {
+            self.items[j].initiative = initiative;
+        }
+        self.sort_creature_list();
+    }
+
+    /// Replace the NPCs of the list with new ones, keeping the players.
+    pub fn replace_npcs(&mut self, npcs: Vec<CreatureItem>) {
+        self.items
+            .retain(|creature| creature.faction == Faction::Player);
+        self.state.select(None);
+        for npc in npcs {
+            self.add_new_creature(npc);
+        }
+    }
+
+    pub fn add_new_creature(&mut self, mut creature_item: CreatureItem) {
+        if creature_item.id == 0 {
+            creature_item.id = self.next_id();
+        }
+        // A new member of a group acts with the rest of it
+        if self.group_initiative && creature_item.faction == Faction::Npc {
+            if let Some(member) = self
+                .items
+                .iter()
+                .find(|c| c.faction == Faction::Npc && c.name == creature_item.name)
+            {
+                creature_item.initiative = member.initiative;
+            }
+        }
         if creature_item.initiative.is_none() {
             self.items.insert(0, creature_item);
         } else {
@@ -319,6 +859,37 @@ impl CreatureList {
         self.sort_creature_list();
     }
 
+    pub fn rest(&mut self, rest: Rest) {
+        for creature in self.items.iter_mut() {
+            creature.rest(rest);
+        }
+    }
+
+    pub fn start_turn(&mut self, i: usize) {
+        if i >= self.items.len() {
+            return;
+        }
+        for j in self.group_members(i) {
+            self.items[j].start_turn();
+        }
+    }
+
+    /// Bring a delayed creature back into the turn order right after the
+    /// creature at `after`, taking over its initiative. Returns the new
+    /// position of the delayed creature.
+    pub fn resume_delayed(&mut self, delayed: usize, after: usize) -> usize {
+        if delayed == after {
+            self.items[delayed].delayed = false;
+            return delayed;
+        }
+        let mut creature = self.items.remove(delayed);
+        let after = if delayed < after { after - 1 } else { after };
+        creature.delayed = false;
+        creature.initiative = self.items[after].initiative;
+        self.items.insert(after + 1, creature);
+        after + 1
+    }
+
     pub fn sort_creature_list(&mut self) {
         self.items.sort_by(|creature_a, creature_b| {
             match (&creature_a.initiative, &creature_b.initiative) {
@@ -327,7 +898,25 @@ impl CreatureList {
                 (_, None) => std::cmp::Ordering::Greater,
                 (Some(creature_a_val), Some(creature_b_val)) => creature_b_val.cmp(creature_a_val),
             }
-        })
+        });
+        if self.group_initiative {
+            self.gather_groups();
+        }
+    }
+
+    /// Move the members of each group next to the first one.
+    fn gather_groups(&mut self) {
+        let mut i = 0;
+        while i < self.items.len() {
+            let members = self.group_members(i);
+            if members.len() > 1 && members[0] == i {
+                for (offset, j) in members.into_iter().enumerate().skip(1) {
+                    let member = self.items.remove(j);
+                    self.items.insert(i + offset, member);
+                }
+            }
+            i += 1;
+        }
     }
 }
 
@@ -337,6 +926,7 @@ impl Default for ApiCreatureSearchItem {
             name: "Test Creature".to_string(),
             desc: Some("Default desc".to_string()),
             size: None,
+            creature_type: None,
             subtype: None,
             group: None,
             alignment: None,
@@ -379,8 +969,23 @@ impl Default for ApiCreatureSearchItem {
     }
 }
 
+static CONFIG_FILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// Use another config file than `default.yml` in the default location, only
+/// the first call has an effect.
+pub fn set_config_file_location(path: &str) {
+    let _ = CONFIG_FILE_OVERRIDE.set(path.to_string());
+}
+
+pub fn get_config_file_location() -> Option<String> {
+    match CONFIG_FILE_OVERRIDE.get() {
+        Some(path) => Some(path.clone()),
+        None => default_config_file_location(),
+    }
+}
+
 #[cfg(unix)]
-fn get_config_file_location() -> Option<String> {
+fn default_config_file_location() -> Option<String> {
     let home = match std::env::var("HOME") {
         Ok(home) => home,
         Err(_) => return None,
@@ -389,7 +994,7 @@ fn get_config_file_location() -> Option<String> {
 }
 
 #[cfg(windows)]
-fn get_config_file_location() -> Option<String> {
+fn default_config_file_location() -> Option<String> {
     let userprofile = match std::env::var("USERPROFILE") {
         Ok(userprofile) => userprofile,
         Err(_) => return None,
@@ -399,10 +1004,7 @@ fn get_config_file_location() -> Option<String> {
 
 impl Default for CreatureList {
     fn default() -> Self {
-        let empty_creature_list = Self {
-            items: Vec::new(),
-            state: ListState::default(),
-        };
+        let empty_creature_list = Self::new(Vec::new());
 
         let config_path: String = match get_config_file_location() {
             Some(path) => path,
@@ -420,55 +1022,120 @@ impl Default for CreatureList {
         };
 
         let doc = &docs[0];
-        let mut items = Vec::new();
+        let mut creature_list = Self::new(players_from_yaml(doc));
+        creature_list.health = HealthConfig::from_yaml(doc);
+        creature_list
+    }
+}
 
-        if let Some(players) = doc["players"].as_vec() {
-            for player in players {
-                let name = player["name"].as_str().unwrap_or("Unknown");
-                let desc = player["desc"].as_str();
-                items.push(CreatureItem::new_player(name, desc));
+/// The `players` section of `default.yml`. The HP of players without
+/// `hit_points` is not tracked.
+pub fn players_from_yaml(doc: &Yaml) -> Vec<CreatureItem> {
+    let Some(players) = doc["players"].as_vec() else {
+        return Vec::new();
+    };
+    players
+        .iter()
+        .map(|player| {
+            let name = player["name"].as_str().unwrap_or("Unknown");
+            let desc = player["desc"].as_str();
+            let mut creature = CreatureItem::new_player(name, desc);
+            creature.level = player["level"].as_i64().map(|level| level as u32);
+            if let Some(hit_points) = player["hit_points"].as_i64() {
+                creature.set_max_hit_points(hit_points.max(1) as u64);
+            }
+            creature
+        })
+        .collect()
+}
+
+/// The row of a creature, with an HP gauge colored by the health thresholds
+/// unless its HP is not tracked.
+pub fn creature_list_item<'a>(value: &CreatureItem, health: &HealthConfig) -> ListItem<'a> {
+    let mut label = match &value.desc {
+        Some(desc) => format!("{} ({})", value.name, desc),
+        None => value.name.clone(),
+    };
+    if value.marked {
+        label = format!("● {label}");
+    }
+    let used = value.economy.to_string();
+    if !used.is_empty() {
+        label.push_str(&format!(" [{used}]"));
+    }
+    if value.delayed {
+        label.push_str(" [Delayed]");
+    }
+    if value.surprised {
+        label.push_str(" [Surprised]");
+    }
+    if let Some(trigger) = &value.readied {
+        label.push_str(&format!(" [Ready: {trigger}]"));
+    }
+    if value.hidden {
+        label.push_str(" [Hidden]");
+    }
+    if !value.effects.is_empty() {
+        label.push_str(" [Effects]");
+    }
+
+    let mut spans = vec![match value.status {
+        Status::Alive => {
+            if value.initiative.is_some() {
+                Span::styled(format!(" ✓ {label}"), COMPLETED_TEXT_FG_COLOR)
+            } else {
+                Span::styled(format!(" ✓ {label}"), NO_INITIATIVE_STYLE)
             }
         }
-        let state = ListState::default();
-        Self { items, state }
-    }
-}
-
-impl From<&CreatureItem> for ListItem<'_> {
-    fn from(value: &CreatureItem) -> Self {
-        if value.desc.is_some() {
-            let line = match value.status {
-                Status::Alive => {
-                    if value.initiative.is_some() {
-                        Line::styled(
-                            format!(" ✓ {} ({})", value.name, value.desc.as_ref().unwrap()),
-                            COMPLETED_TEXT_FG_COLOR,
-                        )
-                    } else {
-                        Line::styled(
-                            format!(" ✓ {} ({})", value.name, value.desc.as_ref().unwrap()),
-                            NO_INITIATIVE_STYLE,
-                        )
-                    }
-                }
-                Status::Dead => Line::styled(
-                    format!(" X {} ({})", value.name, value.desc.as_ref().unwrap()),
-                    DEAD_TEXT_FG_COLOR,
-                ),
-            };
-            ListItem::new(line)
-        } else {
-            let line = match value.status {
-                Status::Alive => {
-                    if value.initiative.is_some() {
-                        Line::styled(format!(" ✓ {}", value.name), COMPLETED_TEXT_FG_COLOR)
-                    } else {
-                        Line::styled(format!(" ✓ {}", value.name), NO_INITIATIVE_STYLE)
-                    }
-                }
-                Status::Dead => Line::styled(format!(" X {}", value.name), DEAD_TEXT_FG_COLOR),
-            };
-            ListItem::new(line)
-        }
+        Status::Dead => Span::styled(format!(" X {label}"), DEAD_TEXT_FG_COLOR),
+    }];
+
+    if value.untracked_hp {
+        return ListItem::new(Line::from(spans));
     }
+    let threshold = health.threshold(value);
+    let gauge_color = match (value.status, threshold) {
+        (Status::Dead, _) => DEAD_TEXT_FG_COLOR,
+        (_, Some(threshold)) => threshold.color,
+        (_, None) => COMPLETED_TEXT_FG_COLOR,
+    };
+    spans.push(Span::styled(
+        format!(
+            " {} {}/{}",
+            hp_gauge(value.hit_points, value.max_hit_points, GAUGE_WIDTH),
+            value.hit_points,
+            value.max_hit_points
+        ),
+        gauge_color,
+    ));
+    if let Some(threshold) = threshold {
+        spans.push(Span::styled(
+            format!(" [{}]", threshold.label),
+            threshold.color,
+        ));
+    }
+    if health.would_flee(value) {
+        spans.push(Span::styled(" [Flee]", FLEE_TEXT_FG_COLOR));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+/// The single row standing in for a collapsed group.
+pub fn group_list_item<'a>(members: &[&CreatureItem]) -> ListItem<'a> {
+    let alive = members.iter().filter(|c| c.status == Status::Alive).count();
+    let label = format!(
+        " ▸ {} x{} ({}/{} alive)",
+        members[0].name,
+        members.len(),
+        alive,
+        members.len()
+    );
+    let color = if alive == 0 {
+        DEAD_TEXT_FG_COLOR
+    } else if members[0].initiative.is_some() {
+        COMPLETED_TEXT_FG_COLOR
+    } else {
+        NO_INITIATIVE_STYLE
+    };
+    ListItem::new(Line::styled(label, color))
 }
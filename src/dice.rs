@@ -0,0 +1,173 @@
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+// The most dice, sides and flat modifier a term can have, so that rolling
+// stays quick and the totals cannot overflow.
+pub const MAX_DICE: u32 = 1000;
+pub const MAX_SIDES: u32 = 1000;
+pub const MAX_MODIFIER: i64 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceError(pub String);
+
+impl fmt::Display for DiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unable to parse dice: {}", self.0)
+    }
+}
+
+impl std::error::Error for DiceError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Term {
+    Dice { count: u32, sides: u32, sign: i64 },
+    Flat(i64),
+}
+
+/// A sum of dice and modifiers, e.g. "2d6 + 1d4 - 1".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceExpr {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceRoll {
+    pub rolls: Vec<i64>,
+    pub total: i64,
+}
+
+impl fmt::Display for DiceRoll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rolls: Vec<String> = self.rolls.iter().map(|r| r.to_string()).collect();
+        write!(f, "[{}] = {}", rolls.join(", "), self.total)
+    }
+}
+
+impl FromStr for DiceExpr {
+    type Err = DiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expr: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        if expr.is_empty() {
+            return Err(DiceError(s.to_string()));
+        }
+
+        let mut terms = Vec::new();
+        let mut sign = 1;
+        let mut leading_sign = false;
+        let mut current = String::new();
+        for c in expr.chars().chain(std::iter::once('+')) {
+            if c == '+' || c == '-' {
+                if current.is_empty() {
+                    // Only one leading sign, e.g. "-1", and a term after it
+                    if !terms.is_empty() || leading_sign {
+                        return Err(DiceError(s.to_string()));
+                    }
+                    leading_sign = true;
+                } else {
+                    terms.push(parse_term(&current, sign).ok_or(DiceError(s.to_string()))?);
+                    current.clear();
+                }
+                sign = if c == '-' { -1 } else { 1 };
+            } else {
+                current.push(c);
+            }
+        }
+        if terms.is_empty() {
+            return Err(DiceError(s.to_string()));
+        }
+        Ok(Self { terms })
+    }
+}
+
+fn parse_term(term: &str, sign: i64) -> Option<Term> {
+    match term.to_lowercase().split_once('d') {
+        Some((count, sides)) => {
+            let count = if count.is_empty() {
+                1
+            } else {
+                count.parse().ok()?
+            };
+            let sides: u32 = sides.parse().ok()?;
+            if sides == 0 || count > MAX_DICE || sides > MAX_SIDES {
+                return None;
+            }
+            Some(Term::Dice { count, sides, sign })
+        }
+        None => {
+            let value: i64 = term.parse().ok()?;
+            if value > MAX_MODIFIER {
+                return None;
+            }
+            Some(Term::Flat(sign * value))
+        }
+    }
+}
+
+impl DiceExpr {
+    pub fn roll(&self) -> DiceRoll {
+        self.roll_with(&mut rand::rng())
+    }
+
+    pub fn roll_with<R: Rng>(&self, rng: &mut R) -> DiceRoll {
+        let mut rolls = Vec::new();
+        let mut total = 0;
+        for term in &self.terms {
+            match *term {
+                Term::Dice { count, sides, sign } => {
+                    for _ in 0..count {
+                        let roll = rng.random_range(1..=sides as i64);
+                        rolls.push(roll);
+                        total += sign * roll;
+                    }
+                }
+                Term::Flat(value) => total += value,
+            }
+        }
+        DiceRoll { rolls, total }
+    }
+
+    /// The same expression with the number of dice doubled, for critical hits.
+    pub fn doubled_dice(&self) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .map(|term| match *term {
+                Term::Dice { count, sides, sign } => Term::Dice {
+                    count: count.saturating_mul(2),
+                    sides,
+                    sign,
+                },
+                flat => flat,
+            })
+            .collect();
+        Self { terms }
+    }
+
+    pub fn min(&self) -> i64 {
+        self.terms
+            .iter()
+            .map(|term| match *term {
+                Term::Dice { count, sides, sign } if sign < 0 => -(count as i64 * sides as i64),
+                Term::Dice { count, .. } => count as i64,
+                Term::Flat(value) => value,
+            })
+            .sum()
+    }
+
+    pub fn max(&self) -> i64 {
+        self.terms
+            .iter()
+            .map(|term| match *term {
+                Term::Dice { count, sign, .. } if sign < 0 => -(count as i64),
+                Term::Dice { count, sides, .. } => count as i64 * sides as i64,
+                Term::Flat(value) => value,
+            })
+            .sum()
+    }
+}
+
+pub fn roll_d20() -> i64 {
+    rand::rng().random_range(1..=20)
+}
@@ -0,0 +1,233 @@
+use crate::creature::{CreatureList, Faction};
+use std::fmt;
+
+/// Parse a challenge rating such as "1/4", "0.5" or "12".
+pub fn parse_challenge_rating(cr: &str) -> Option<f64> {
+    let cr = cr.trim();
+    let cr = match cr.split_once('/') {
+        Some((numerator, denominator)) => {
+            let numerator: f64 = numerator.trim().parse().ok()?;
+            let denominator: f64 = denominator.trim().parse().ok()?;
+            if denominator == 0.0 {
+                return None;
+            }
+            numerator / denominator
+        }
+        None => cr.parse().ok()?,
+    };
+    (0.0..=30.0).contains(&cr).then_some(cr)
+}
+
+/// The experience points of a challenge rating from the Monster Manual.
+pub fn xp_for_challenge_rating(cr: f64) -> Option<u64> {
+    const FRACTIONS: [(f64, u64); 4] = [(0.0, 10), (0.125, 25), (0.25, 50), (0.5, 100)];
+    const WHOLE: [u64; 30] = [
+        200, 450, 700, 1100, 1800, 2300, 2900, 3900, 5000, 5900, 7200, 8400, 10000, 11500, 13000,
+        15000, 18000, 20000, 22000, 25000, 33000, 41000, 50000, 62000, 75000, 90000, 105000,
+        120000, 135000, 155000,
+    ];
+    if let Some((_, xp)) = FRACTIONS.iter().find(|(fraction, _)| *fraction == cr) {
+        return Some(*xp);
+    }
+    if cr.fract() != 0.0 || !(1.0..=30.0).contains(&cr) {
+        return None;
+    }
+    WHOLE.get(cr as usize - 1).copied()
+}
+
+pub fn xp_for(cr: &str) -> Option<u64> {
+    parse_challenge_rating(cr).and_then(xp_for_challenge_rating)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    Deadly,
+}
+
+impl Difficulty {
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Trivial | Difficulty::Deadly => Difficulty::Easy,
+            Difficulty::Easy => Difficulty::Medium,
+            Difficulty::Medium => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Deadly,
+        }
+    }
+
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Deadly,
+    ];
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Trivial => write!(f, "Trivial"),
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+            Difficulty::Deadly => write!(f, "Deadly"),
+        }
+    }
+}
+
+/// The easy, medium, hard and deadly XP thresholds of one character.
+pub fn xp_thresholds(level: u32) -> [u64; 4] {
+    const THRESHOLDS: [[u64; 4]; 20] = [
+        [25, 50, 75, 100],
+        [50, 100, 150, 200],
+        [75, 150, 225, 400],
+        [125, 250, 375, 500],
+        [250, 500, 750, 1100],
+        [300, 600, 900, 1400],
+        [350, 750, 1100, 1700],
+        [450, 900, 1400, 2100],
+        [550, 1100, 1600, 2400],
+        [600, 1200, 1900, 2800],
+        [800, 1600, 2400, 3600],
+        [1000, 2000, 3000, 4500],
+        [1100, 2200, 3400, 5100],
+        [1250, 2500, 3800, 5700],
+        [1400, 2800, 4300, 6400],
+        [1600, 3200, 4800, 7200],
+        [2000, 3900, 5900, 8800],
+        [2100, 4200, 6300, 9500],
+        [2400, 4900, 7300, 10900],
+        [2800, 5700, 8500, 12700],
+    ];
+    THRESHOLDS[level.clamp(1, 20) as usize - 1]
+}
+
+/// The multiplier for fighting several monsters at once. Parties of fewer
+/// than three use the next higher multiplier, parties of six or more the next
+/// lower one.
+pub fn encounter_multiplier(monsters: usize, party_size: usize) -> f64 {
+    const MULTIPLIERS: [f64; 8] = [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 4.0, 5.0];
+    let i = match monsters {
+        0 | 1 => 1,
+        2 => 2,
+        3..=6 => 3,
+        7..=10 => 4,
+        11..=14 => 5,
+        _ => 6,
+    };
+    let i = match party_size {
+        1 | 2 => i + 1,
+        size if size >= 6 => i - 1,
+        _ => i,
+    };
+    MULTIPLIERS[i]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncounterSummary {
+    pub party_levels: Vec<u32>,
+    pub monsters: usize,
+    pub base_xp: u64,
+    pub adjusted_xp: u64,
+    pub thresholds: [u64; 4],
+    pub difficulty: Difficulty,
+}
+
+impl EncounterSummary {
+    /// The XP every player gets when the encounter is over.
+    pub fn xp_per_player(&self) -> u64 {
+        match self.party_levels.len() as u64 {
+            0 => 0,
+            size => self.base_xp / size,
+        }
+    }
+}
+
+impl fmt::Display for EncounterSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Party: {} players, levels {}",
+            self.party_levels.len(),
+            self.party_levels
+                .iter()
+                .map(|level| level.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        let thresholds: Vec<String> = Difficulty::ALL
+            .iter()
+            .zip(self.thresholds)
+            .map(|(difficulty, xp)| format!("{difficulty}: {xp}"))
+            .collect();
+        writeln!(f, "Thresholds: {}", thresholds.join(", "))?;
+        writeln!(
+            f,
+            "Monsters: {}, XP: {}, adjusted XP: {}",
+            self.monsters, self.base_xp, self.adjusted_xp
+        )?;
+        writeln!(f, "Difficulty: {}", self.difficulty)?;
+        write!(
+            f,
+            "XP to award: {} ({} per player)",
+            self.base_xp,
+            self.xp_per_player()
+        )
+    }
+}
+
+/// Rate the NPCs of the encounter against the levels of the players. Players
+/// without a level are left out of the party.
+pub fn encounter_summary(creature_list: &CreatureList) -> EncounterSummary {
+    let party_levels: Vec<u32> = creature_list
+        .items
+        .iter()
+        .filter(|creature| creature.faction == Faction::Player)
+        .filter_map(|creature| creature.level)
+        .collect();
+    let monster_xp: Vec<u64> = creature_list
+        .items
+        .iter()
+        .filter(|creature| creature.faction == Faction::Npc)
+        .filter_map(|creature| creature.challenge_rating.as_deref().and_then(xp_for))
+        .collect();
+    rate_encounter(&monster_xp, &party_levels)
+}
+
+/// Rate monsters worth the given XP against a party of the given levels.
+pub fn rate_encounter(monster_xp: &[u64], party_levels: &[u32]) -> EncounterSummary {
+    let base_xp = monster_xp.iter().sum();
+    let adjusted_xp =
+        (base_xp as f64 * encounter_multiplier(monster_xp.len(), party_levels.len())) as u64;
+
+    let thresholds = party_thresholds(party_levels);
+    let difficulty = Difficulty::ALL
+        .iter()
+        .zip(thresholds)
+        .rev()
+        .find(|(_, threshold)| *threshold > 0 && adjusted_xp >= *threshold)
+        .map_or(Difficulty::Trivial, |(difficulty, _)| *difficulty);
+
+    EncounterSummary {
+        party_levels: party_levels.to_vec(),
+        monsters: monster_xp.len(),
+        base_xp,
+        adjusted_xp,
+        thresholds,
+        difficulty,
+    }
+}
+
+/// The easy, medium, hard and deadly XP thresholds of the whole party.
+pub fn party_thresholds(party_levels: &[u32]) -> [u64; 4] {
+    let mut thresholds = [0; 4];
+    for level in party_levels {
+        for (total, xp) in thresholds.iter_mut().zip(xp_thresholds(*level)) {
+            *total += xp;
+        }
+    }
+    thresholds
+}
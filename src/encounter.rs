@@ -0,0 +1,214 @@
+use crate::creature::{get_config_file_location, CreatureList, Faction};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::{Yaml, YamlEmitter, YamlLoader};
+
+/// The most monsters of one kind a template can hold.
+pub const MAX_MONSTER_COUNT: i64 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncounterMonster {
+    pub name: String,
+    pub count: u32,
+}
+
+/// How the hit points of the monsters are set when the encounter is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HpMode {
+    #[default]
+    Average,
+    Rolled,
+}
+
+impl fmt::Display for HpMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HpMode::Average => write!(f, "average"),
+            HpMode::Rolled => write!(f, "rolled"),
+        }
+    }
+}
+
+/// A prepared encounter, stored as a YAML file:
+///
+/// ```yaml
+/// name: Goblin ambush
+/// notes: They hide in the bushes
+/// hp_mode: rolled
+/// monsters:
+///   - name: Goblin
+///     count: 4
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EncounterTemplate {
+    pub name: String,
+    pub notes: Option<String>,
+    pub hp_mode: HpMode,
+    pub monsters: Vec<EncounterMonster>,
+}
+
+impl fmt::Display for EncounterTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let monsters: Vec<String> = self
+            .monsters
+            .iter()
+            .map(|monster| format!("{}x {}", monster.count, monster.name))
+            .collect();
+        write!(
+            f,
+            "{}: {} ({} HP)",
+            self.name,
+            monsters.join(", "),
+            self.hp_mode
+        )
+    }
+}
+
+impl EncounterTemplate {
+    /// Count the NPCs of the list by name, in the order they appear.
+    pub fn from_creature_list(name: &str, creature_list: &CreatureList) -> Self {
+        let mut monsters: Vec<EncounterMonster> = Vec::new();
+        for creature in &creature_list.items {
+            if creature.faction != Faction::Npc {
+                continue;
+            }
+            match monsters.iter_mut().find(|m| m.name == creature.name) {
+                Some(monster) => monster.count += 1,
+                None => monsters.push(EncounterMonster {
+                    name: creature.name.clone(),
+                    count: 1,
+                }),
+            }
+        }
+        Self {
+            name: name.to_string(),
+            monsters,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_yaml(yaml_str: &str) -> Result<Self, String> {
+        let docs = YamlLoader::load_from_str(yaml_str).map_err(|e| e.to_string())?;
+        let doc = docs.first().ok_or("Empty encounter file")?;
+        let name = doc["name"].as_str().ok_or("Encounter has no name")?;
+        let mut monsters = Vec::new();
+        for monster in doc["monsters"].as_vec().into_iter().flatten() {
+            let Some(monster_name) = monster["name"].as_str() else {
+                continue;
+            };
+            let count = monster["count"].as_i64().unwrap_or(1).max(1);
+            if count > MAX_MONSTER_COUNT {
+                return Err(format!(
+                    "{monster_name}: a count of {count} is more than {MAX_MONSTER_COUNT}"
+                ));
+            }
+            monsters.push(EncounterMonster {
+                name: monster_name.to_string(),
+                count: count as u32,
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            notes: doc["notes"].as_str().map(|notes| notes.to_string()),
+            hp_mode: match doc["hp_mode"].as_str() {
+                Some("rolled") => HpMode::Rolled,
+                _ => HpMode::Average,
+            },
+            monsters,
+        })
+    }
+
+    pub fn to_yaml(&self) -> String {
+        let mut doc = Hash::new();
+        doc.insert(key("name"), Yaml::String(self.name.clone()));
+        if let Some(notes) = &self.notes {
+            doc.insert(key("notes"), Yaml::String(notes.clone()));
+        }
+        doc.insert(key("hp_mode"), Yaml::String(self.hp_mode.to_string()));
+        let monsters = self
+            .monsters
+            .iter()
+            .map(|monster| {
+                let mut entry = Hash::new();
+                entry.insert(key("name"), Yaml::String(monster.name.clone()));
+                entry.insert(key("count"), Yaml::Integer(monster.count as i64));
+                Yaml::Hash(entry)
+            })
+            .collect();
+        doc.insert(key("monsters"), Yaml::Array(monsters));
+
+        let mut out = String::new();
+        // Writing into a String can't fail
+        let _ = YamlEmitter::new(&mut out).dump(&Yaml::Hash(doc));
+        out.push('\n');
+        out
+    }
+
+    /// The file name of the template, e.g. "Goblin ambush" becomes
+    /// "goblin-ambush.yml".
+    pub fn file_name(&self) -> String {
+        let slug: Vec<String> = self
+            .name
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.to_lowercase())
+            .collect();
+        format!("{}.yml", slug.join("-"))
+    }
+
+    /// Write it to the encounters directory, an existing template with the
+    /// same file name is never overwritten.
+    pub fn save(&self, dir: &Path) -> io::Result<PathBuf> {
+        if !self.name.chars().any(char::is_alphanumeric) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the name needs a letter or a digit",
+            ));
+        }
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| match e.kind() {
+                io::ErrorKind::AlreadyExists => io::Error::new(
+                    e.kind(),
+                    format!("{} already exists, pick another name", path.display()),
+                ),
+                _ => e,
+            })?;
+        file.write_all(self.to_yaml().as_bytes())?;
+        Ok(path)
+    }
+}
+
+fn key(name: &str) -> Yaml {
+    Yaml::String(name.to_string())
+}
+
+/// Encounter templates are kept in an `encounters` directory next to
+/// `default.yml`.
+pub fn get_encounters_dir() -> Option<PathBuf> {
+    let config_path = get_config_file_location()?;
+    Some(Path::new(&config_path).parent()?.join("encounters"))
+}
+
+/// Every template in the directory sorted by name, files that can't be read
+/// are skipped.
+pub fn load_templates(dir: &Path) -> Vec<EncounterTemplate> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<EncounterTemplate> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "yml"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|yaml_str| EncounterTemplate::from_yaml(&yaml_str).ok())
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    templates
+}
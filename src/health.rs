@@ -0,0 +1,139 @@
+use crate::creature::{CreatureItem, Faction, Status};
+use ratatui::style::palette::tailwind::ORANGE;
+use ratatui::style::Color;
+use yaml_rust2::Yaml;
+
+pub const GAUGE_WIDTH: u64 = 10;
+
+/// A label and color for creatures at or below a percentage of their HP.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthThreshold {
+    pub percent: u64,
+    pub label: String,
+    pub color: Color,
+}
+
+/// How hurt creatures are shown in the list, from the `health` section of
+/// `default.yml`:
+///
+/// ```yaml
+/// health:
+///   thresholds:
+///     - percent: 50
+///       label: Bloodied
+///       color: yellow
+///     - percent: 25
+///       label: Critical
+///       color: red
+///   morale: 30
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthConfig {
+    /// Highest percentage first.
+    pub thresholds: Vec<HealthThreshold>,
+    /// NPCs at or below this percentage of their HP would flee.
+    pub morale: Option<u64>,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        HealthConfig {
+            thresholds: vec![HealthThreshold {
+                percent: 50,
+                label: "Bloodied".to_string(),
+                color: ORANGE.c500,
+            }],
+            morale: None,
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Read the `health` section of the config, the defaults for anything
+    /// left out. Thresholds with an unknown color are skipped.
+    pub fn from_yaml(doc: &Yaml) -> Self {
+        let mut config = HealthConfig::default();
+        let health = &doc["health"];
+        if let Some(thresholds) = health["thresholds"].as_vec() {
+            config.thresholds = thresholds
+                .iter()
+                .filter_map(|threshold| {
+                    Some(HealthThreshold {
+                        percent: threshold["percent"].as_i64()?.clamp(0, 100) as u64,
+                        label: threshold["label"].as_str()?.to_string(),
+                        color: threshold["color"].as_str()?.parse().ok()?,
+                    })
+                })
+                .collect();
+            config
+                .thresholds
+                .sort_by_key(|threshold| std::cmp::Reverse(threshold.percent));
+        }
+        config.morale = health["morale"]
+            .as_i64()
+            .map(|morale| morale.clamp(0, 100) as u64);
+        config
+    }
+
+    /// The lowest threshold the creature has reached, none once it is down.
+    pub fn threshold(&self, creature: &CreatureItem) -> Option<&HealthThreshold> {
+        if creature.hit_points == 0 {
+            return None;
+        }
+        self.thresholds
+            .iter()
+            .rev()
+            .find(|threshold| at_or_below(creature, threshold.percent))
+    }
+
+    /// How hurt the creature looks without its HP: "Down", the label of the
+    /// lowest threshold it has reached, "Hurt" or "Unhurt".
+    pub fn descriptor(&self, creature: &CreatureItem) -> String {
+        if creature.hit_points == 0 {
+            "Down".to_string()
+        } else if let Some(threshold) = self.threshold(creature) {
+            threshold.label.clone()
+        } else if creature.hit_points < creature.max_hit_points {
+            "Hurt".to_string()
+        } else {
+            "Unhurt".to_string()
+        }
+    }
+
+    /// Whether a living NPC has lost enough HP to flee.
+    pub fn would_flee(&self, creature: &CreatureItem) -> bool {
+        creature.faction == Faction::Npc
+            && creature.status == Status::Alive
+            && creature.hit_points > 0
+            && self
+                .morale
+                .is_some_and(|morale| at_or_below(creature, morale))
+    }
+
+    /// Whether losing HP from `before` is what makes the creature flee.
+    pub fn breaks_morale(&self, creature: &CreatureItem, before: u64) -> bool {
+        self.would_flee(creature)
+            && self
+                .morale
+                .is_some_and(|morale| before * 100 > morale * creature.max_hit_points)
+    }
+}
+
+fn at_or_below(creature: &CreatureItem, percent: u64) -> bool {
+    creature.hit_points * 100 <= percent * creature.max_hit_points
+}
+
+/// A bar of the remaining HP, e.g. "███████░░░". Any HP left shows at least
+/// one block.
+pub fn hp_gauge(hit_points: u64, max_hit_points: u64, width: u64) -> String {
+    let filled = if max_hit_points == 0 || hit_points == 0 {
+        0
+    } else {
+        (hit_points.min(max_hit_points) * width).div_ceil(max_hit_points)
+    };
+    format!(
+        "{}{}",
+        "█".repeat(filled as usize),
+        "░".repeat((width - filled) as usize)
+    )
+}
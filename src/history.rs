@@ -0,0 +1,214 @@
+use crate::creature::{CreatureItem, CreatureList, Status};
+use std::fmt;
+
+/// The most commands kept for undo.
+const MAX_HISTORY: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    HitPoints {
+        before: u64,
+        after: u64,
+    },
+    Initiative {
+        before: Option<i64>,
+        after: Option<i64>,
+    },
+    Description {
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+/// A reversible change to the creature list. Creatures are found by id as
+/// sorting moves them around.
+#[derive(Debug, Clone)]
+pub enum Command {
+    Edit {
+        id: u64,
+        name: String,
+        edit: Edit,
+    },
+    Insert {
+        creature: CreatureItem,
+    },
+    Delete {
+        index: usize,
+        creature: CreatureItem,
+    },
+    /// Several commands undone and redone together, e.g. loading an
+    /// encounter.
+    Batch {
+        name: String,
+        commands: Vec<Command>,
+    },
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let initiative = |initiative: &Option<i64>| match initiative {
+            Some(initiative) => initiative.to_string(),
+            None => "-".to_string(),
+        };
+        match self {
+            Command::Edit { name, edit, .. } => match edit {
+                Edit::HitPoints { before, after } => write!(f, "{name}: HP {before} -> {after}"),
+                Edit::Initiative { before, after } => write!(
+                    f,
+                    "{name}: initiative {} -> {}",
+                    initiative(before),
+                    initiative(after)
+                ),
+                Edit::Description { after, .. } => write!(
+                    f,
+                    "{name}: description \"{}\"",
+                    after.as_deref().unwrap_or_default()
+                ),
+            },
+            Command::Insert { creature } => write!(f, "Add {}", creature.name),
+            Command::Delete { creature, .. } => write!(f, "Delete {}", creature.name),
+            Command::Batch { name, .. } => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Command {
+    fn apply(&self, creature_list: &mut CreatureList, undo: bool) {
+        match self {
+            Command::Edit { id, edit, .. } => {
+                let Some(i) = creature_list.position(*id) else {
+                    return;
+                };
+                match edit {
+                    Edit::HitPoints { before, after } => {
+                        let creature = &mut creature_list.items[i];
+                        creature.hit_points = if undo { *before } else { *after };
+                        creature.status = if creature.hit_points == 0 {
+                            Status::Dead
+                        } else {
+                            Status::Alive
+                        };
+                    }
+                    Edit::Initiative { before, after } => {
+                        creature_list.set_initiative(i, if undo { *before } else { *after });
+                    }
+                    Edit::Description { before, after } => {
+                        let desc = if undo { before } else { after };
+                        creature_list.items[i].desc = desc.clone();
+                    }
+                }
+            }
+            Command::Insert { creature } if undo => remove(creature_list, creature.id),
+            Command::Insert { creature } => creature_list.add_new_creature(creature.clone()),
+            Command::Delete { index, creature } if undo => {
+                let index = (*index).min(creature_list.items.len());
+                creature_list.items.insert(index, creature.clone());
+            }
+            Command::Delete { creature, .. } => remove(creature_list, creature.id),
+            Command::Batch { commands, .. } if undo => {
+                for command in commands.iter().rev() {
+                    command.apply(creature_list, true);
+                }
+            }
+            Command::Batch { commands, .. } => {
+                for command in commands {
+                    command.apply(creature_list, false);
+                }
+            }
+        }
+    }
+}
+
+/// Deletions of the creatures matching `delete`, from the back so undoing puts
+/// each one back at its index.
+pub fn deletions(
+    creature_list: &CreatureList,
+    delete: impl Fn(&CreatureItem) -> bool,
+) -> Vec<Command> {
+    creature_list
+        .items
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, creature)| delete(creature))
+        .map(|(index, creature)| Command::Delete {
+            index,
+            creature: creature.clone(),
+        })
+        .collect()
+}
+
+fn remove(creature_list: &mut CreatureList, id: u64) {
+    if let Some(i) = creature_list.position(id) {
+        creature_list.items.remove(i);
+        if creature_list.items.is_empty() {
+            creature_list.state.select(None);
+        } else if let Some(selected) = creature_list.state.selected() {
+            creature_list
+                .state
+                .select(Some(selected.min(creature_list.items.len() - 1)));
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    done: Vec<Command>,
+    undone: Vec<Command>,
+}
+
+impl History {
+    /// Record a command that was just applied. Consecutive HP changes of the
+    /// same creature are merged into one.
+    pub fn record(&mut self, command: Command) {
+        self.undone.clear();
+        if let (
+            Some(Command::Edit {
+                id: last_id,
+                edit: Edit::HitPoints { after: last, .. },
+                ..
+            }),
+            Command::Edit {
+                id,
+                edit: Edit::HitPoints { after, .. },
+                ..
+            },
+        ) = (self.done.last_mut(), &command)
+        {
+            if *last_id == *id {
+                *last = *after;
+                return;
+            }
+        }
+        self.done.push(command);
+        if self.done.len() > MAX_HISTORY {
+            self.done.remove(0);
+        }
+    }
+
+    /// Revert the last command, returning it.
+    pub fn undo(&mut self, creature_list: &mut CreatureList) -> Option<&Command> {
+        let command = self.done.pop()?;
+        command.apply(creature_list, true);
+        self.undone.push(command);
+        self.undone.last()
+    }
+
+    /// Apply the last undone command again, returning it.
+    pub fn redo(&mut self, creature_list: &mut CreatureList) -> Option<&Command> {
+        let command = self.undone.pop()?;
+        command.apply(creature_list, false);
+        self.done.push(command);
+        self.done.last()
+    }
+
+    /// Applied commands, the most recent first.
+    pub fn done(&self) -> impl Iterator<Item = &Command> {
+        self.done.iter().rev()
+    }
+
+    /// Undone commands, the next one to redo first.
+    pub fn undone(&self) -> impl Iterator<Item = &Command> {
+        self.undone.iter().rev()
+    }
+}
@@ -15,6 +15,37 @@ pub struct KeyBindings {
     pub delete_creature: char,
     pub set_creature_description: char,
     pub duplicate_creature: char,
+    pub show_limited_uses: char,
+    pub short_rest: char,
+    pub long_rest: char,
+    pub show_spellcasting: char,
+    pub search_for_spell: char,
+    pub search_reference: char,
+    pub show_conditions: char,
+    pub toggle_action: char,
+    pub toggle_bonus_action: char,
+    pub toggle_reaction: char,
+    pub delay_turn: char,
+    pub ready_action: char,
+    pub start_combat: char,
+    pub toggle_surprised: char,
+    pub toggle_group_initiative: char,
+    pub toggle_group_collapsed: char,
+    pub mob_attack: char,
+    pub mark_creature: char,
+    pub area_effect: char,
+    pub roll_check: char,
+    pub show_senses: char,
+    pub show_difficulty: char,
+    pub encounter_builder: char,
+    pub encounter_templates: char,
+    pub undo: char,
+    pub redo: char,
+    pub show_history: char,
+    pub show_log: char,
+    pub toggle_hidden: char,
+    pub ongoing_effects: char,
+    pub zone_effects: char,
 }
 
 impl Default for KeyBindings {
@@ -35,6 +66,37 @@ impl Default for KeyBindings {
             delete_creature: 'D',
             set_creature_description: 'd',
             duplicate_creature: 'x',
+            show_limited_uses: 'a',
+            short_rest: 'z',
+            long_rest: 'Z',
+            show_spellcasting: 'S',
+            search_for_spell: 'p',
+            search_reference: '?',
+            show_conditions: 'C',
+            toggle_action: 'A',
+            toggle_bonus_action: 'b',
+            toggle_reaction: 'r',
+            delay_turn: 'w',
+            ready_action: 'y',
+            start_combat: 'B',
+            toggle_surprised: 'm',
+            toggle_group_initiative: 'g',
+            toggle_group_collapsed: 'o',
+            mob_attack: 'M',
+            mark_creature: 'v',
+            area_effect: 'F',
+            roll_check: 'R',
+            show_senses: 'P',
+            show_difficulty: 'X',
+            encounter_builder: 'E',
+            encounter_templates: 'T',
+            undo: 'U',
+            redo: 'Y',
+            show_history: 'H',
+            show_log: 'L',
+            toggle_hidden: 'V',
+            ongoing_effects: 'O',
+            zone_effects: 'W',
         }
     }
 }
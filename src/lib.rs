@@ -1,4 +1,24 @@
+pub mod abilities;
 pub mod api;
+pub mod area_effect;
+pub mod builder;
+pub mod checks;
+pub mod cli;
+pub mod combat_log;
+pub mod control;
 pub mod creature;
+pub mod dice;
+pub mod difficulty;
+pub mod encounter;
+pub mod health;
+pub mod history;
 pub mod keybindings;
+pub mod mob;
+pub mod ongoing;
+pub mod player_view;
+pub mod reference;
+pub mod senses;
+pub mod spells;
+pub mod stat_block;
 pub mod ui;
+pub mod zone;
@@ -1,11 +1,108 @@
 use color_eyre::Result;
+use std::fs;
+use std::process::ExitCode;
+use tokio::net::TcpListener;
+use wtii::api::{search_for_creature, MonsterSearch};
+use wtii::cli::{parse_args, CliCommand, USAGE};
+use wtii::creature::{set_config_file_location, CreatureItem};
+use wtii::dice::DiceExpr;
+use wtii::encounter::EncounterTemplate;
+use wtii::stat_block::{render_stat_blocks, StatBlockFormat};
 use wtii::ui;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     color_eyre::install()?;
-    let terminal = ratatui::init();
-    let app_result = ui::App::default().run(terminal).await;
-    ratatui::restore();
-    app_result
+    let cli = match parse_args(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{e}");
+            return Ok(ExitCode::from(2));
+        }
+    };
+    if let Some(config) = &cli.config {
+        set_config_file_location(config);
+    }
+
+    match cli.command {
+        CliCommand::Help => println!("{USAGE}"),
+        CliCommand::Roll { expr } => match expr.parse::<DiceExpr>() {
+            Ok(dice) => println!("{expr}: {}", dice.roll()),
+            Err(e) => {
+                eprintln!("{e}");
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+        CliCommand::Search { name, format } => return search(&name, format).await,
+        CliCommand::Tui {
+            load,
+            serve,
+            players,
+        } => {
+            let mut app = ui::App::default();
+            if let Some(path) = load {
+                let template = fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|yaml_str| EncounterTemplate::from_yaml(&yaml_str));
+                match template {
+                    Ok(template) => app.load_template(template),
+                    Err(e) => {
+                        eprintln!("Unable to load {path}: {e}");
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            }
+            if let Some(port) = serve {
+                match TcpListener::bind(("127.0.0.1", port)).await {
+                    Ok(listener) => app.serve_control(listener),
+                    Err(e) => {
+                        eprintln!("Unable to listen on port {port}: {e}");
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            }
+            if let Some(port) = players {
+                match TcpListener::bind(("127.0.0.1", port)).await {
+                    Ok(listener) => app.serve_player_view(listener),
+                    Err(e) => {
+                        eprintln!("Unable to listen on port {port}: {e}");
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            }
+            let terminal = ratatui::init();
+            let app_result = app.run(terminal).await;
+            ratatui::restore();
+            app_result?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print the stat block of the monster with the name, or of every match when
+/// none has exactly that name.
+async fn search(name: &str, format: StatBlockFormat) -> Result<ExitCode> {
+    let results = match search_for_creature(&MonsterSearch, name).await {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("{e}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    if results.is_empty() {
+        eprintln!("No monster found for {name}");
+        return Ok(ExitCode::FAILURE);
+    }
+    let exact: Vec<_> = results
+        .iter()
+        .filter(|result| result.name.eq_ignore_ascii_case(name))
+        .collect();
+    let matches = if exact.is_empty() {
+        results.iter().collect()
+    } else {
+        exact
+    };
+    let creatures: Vec<CreatureItem> = matches.into_iter().map(CreatureItem::new_npc).collect();
+    println!("{}", render_stat_blocks(&creatures, format));
+    Ok(ExitCode::SUCCESS)
 }
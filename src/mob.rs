@@ -0,0 +1,112 @@
+use crate::dice::{roll_d20, DiceExpr};
+use std::fmt;
+
+/// How a mob attack is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MobMode {
+    /// The DMG mob table, no attack rolls.
+    #[default]
+    Table,
+    /// Roll an attack for every attacker.
+    RollAll,
+}
+
+impl fmt::Display for MobMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MobMode::Table => write!(f, "Mob table"),
+            MobMode::RollAll => write!(f, "Roll every attack"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MobAttack {
+    pub attackers: u32,
+    pub d20_needed: i64,
+    pub hits: u32,
+    pub criticals: u32,
+    pub damage_rolls: Vec<i64>,
+    pub total_damage: i64,
+}
+
+impl fmt::Display for MobAttack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} attackers needing {} on the d20: {} hit",
+            self.attackers, self.d20_needed, self.hits
+        )?;
+        if self.criticals > 0 {
+            write!(f, " ({} critical)", self.criticals)?;
+        }
+        if !self.damage_rolls.is_empty() {
+            let rolls: Vec<String> = self.damage_rolls.iter().map(|r| r.to_string()).collect();
+            write!(f, "\nDamage: {} = {}", rolls.join(" + "), self.total_damage)?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of attackers needed for one of them to hit, from the mob
+/// table of the DMG.
+pub fn attackers_per_hit(d20_needed: i64) -> Option<u32> {
+    match d20_needed {
+        i64::MIN..=5 => Some(1),
+        6..=12 => Some(2),
+        13..=14 => Some(3),
+        15..=16 => Some(4),
+        17..=18 => Some(5),
+        19 => Some(10),
+        20 => Some(20),
+        _ => None,
+    }
+}
+
+/// Resolve the attacks of a mob of identical creatures against a target.
+pub fn resolve_mob_attack(
+    attackers: u32,
+    attack_bonus: i64,
+    target_ac: i64,
+    damage_dice: Option<&DiceExpr>,
+    mode: MobMode,
+) -> MobAttack {
+    let d20_needed = target_ac - attack_bonus;
+    let (hits, criticals) = match mode {
+        MobMode::Table => {
+            let hits = attackers_per_hit(d20_needed).map_or(0, |per_hit| attackers / per_hit);
+            (hits, 0)
+        }
+        MobMode::RollAll => {
+            let rolls: Vec<i64> = (0..attackers).map(|_| roll_d20()).collect();
+            let criticals = rolls.iter().filter(|&&r| r == 20).count() as u32;
+            let hits = rolls
+                .iter()
+                .filter(|&&r| r == 20 || (r != 1 && r >= d20_needed))
+                .count() as u32;
+            (hits, criticals)
+        }
+    };
+
+    let mut damage_rolls = Vec::new();
+    if let Some(damage_dice) = damage_dice {
+        for hit in 0..hits {
+            let roll = if hit < criticals {
+                damage_dice.doubled_dice().roll()
+            } else {
+                damage_dice.roll()
+            };
+            damage_rolls.push(roll.total.max(0));
+        }
+    }
+    let total_damage = damage_rolls.iter().sum();
+
+    MobAttack {
+        attackers,
+        d20_needed,
+        hits,
+        criticals,
+        damage_rolls,
+        total_damage,
+    }
+}
@@ -0,0 +1,318 @@
+use crate::area_effect::{apply_defense, defense_against};
+use crate::checks::Ability;
+use crate::creature::CreatureItem;
+use crate::dice::{DiceExpr, DiceRoll};
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Trigger {
+    #[default]
+    TurnStart,
+    TurnEnd,
+}
+
+impl Trigger {
+    pub fn next(self) -> Self {
+        match self {
+            Trigger::TurnStart => Trigger::TurnEnd,
+            Trigger::TurnEnd => Trigger::TurnStart,
+        }
+    }
+}
+
+impl fmt::Display for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trigger::TurnStart => write!(f, "start of turn"),
+            Trigger::TurnEnd => write!(f, "end of turn"),
+        }
+    }
+}
+
+impl FromStr for Trigger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "start" | "start of turn" => Ok(Trigger::TurnStart),
+            "end" | "end of turn" => Ok(Trigger::TurnEnd),
+            _ => Err(format!("Expected start or end, got {s}")),
+        }
+    }
+}
+
+/// Healing or damage each time the effect fires, e.g. "+10" or "1d6 fire".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HitPointsEffect {
+    pub healing: bool,
+    pub dice: DiceExpr,
+    /// The dice as they were written, to show them.
+    pub expr: String,
+    pub damage_type: Option<String>,
+}
+
+impl FromStr for HitPointsEffect {
+    type Err = String;
+
+    /// A leading "+" heals, anything else is damage with an optional damage
+    /// type after the dice.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (healing, rest) = match s.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('-').unwrap_or(s)),
+        };
+        let mut words: Vec<&str> = rest.split_whitespace().collect();
+        let damage_type = match words.last() {
+            Some(word) if !healing && word.chars().all(|c| c.is_alphabetic()) => {
+                let damage_type = word.to_lowercase();
+                words.pop();
+                Some(damage_type)
+            }
+            _ => None,
+        };
+        let expr = words.join(" ");
+        let dice: DiceExpr = expr.parse().map_err(|e| format!("{e}"))?;
+        Ok(HitPointsEffect {
+            healing,
+            dice,
+            expr,
+            damage_type,
+        })
+    }
+}
+
+impl fmt::Display for HitPointsEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.healing {
+            return write!(f, "heals {}", self.expr);
+        }
+        write!(f, "{}", self.expr)?;
+        if let Some(damage_type) = &self.damage_type {
+            write!(f, " {damage_type}")?;
+        }
+        write!(f, " damage")
+    }
+}
+
+/// Parse a repeated save like "CON 13".
+pub fn parse_save(s: &str) -> Result<(Ability, i64), String> {
+    let (ability, dc) = s
+        .trim()
+        .split_once(' ')
+        .ok_or_else(|| format!("Expected an ability and a DC, got {s}"))?;
+    let ability: Ability = ability.parse()?;
+    let dc = dc
+        .trim()
+        .parse()
+        .map_err(|_| "DC must be a number".to_string())?;
+    Ok((ability, dc))
+}
+
+/// Something on a creature that fires at the start or end of its turn, like
+/// a troll's regeneration or burning oil.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OngoingEffect {
+    pub name: String,
+    pub trigger: Trigger,
+    pub hit_points: Option<HitPointsEffect>,
+    /// A save against the DC that ends the effect on a success.
+    pub save: Option<(Ability, i64)>,
+    /// Damage types that stop the healing until the effect has fired once.
+    pub suppressed_by: Vec<String>,
+    pub suppressed: bool,
+}
+
+impl OngoingEffect {
+    pub fn new(name: &str, trigger: Trigger) -> Self {
+        OngoingEffect {
+            name: name.to_string(),
+            trigger,
+            hit_points: None,
+            save: None,
+            suppressed_by: Vec::new(),
+            suppressed: false,
+        }
+    }
+
+    pub fn suppressed_by(&self, damage_type: &str) -> bool {
+        self.suppressed_by
+            .iter()
+            .any(|suppressed_by| suppressed_by.eq_ignore_ascii_case(damage_type.trim()))
+    }
+}
+
+impl fmt::Display for OngoingEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.trigger)?;
+        if let Some(hit_points) = &self.hit_points {
+            write!(f, ": {hit_points}")?;
+        }
+        if let Some((ability, dc)) = self.save {
+            write!(f, ", {ability} save DC {dc} ends it")?;
+        }
+        if !self.suppressed_by.is_empty() {
+            write!(f, ", stopped by {}", self.suppressed_by.join(", "))?;
+        }
+        if self.suppressed {
+            write!(f, " [Suppressed]")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectSave {
+    pub ability: Ability,
+    pub dc: i64,
+    pub roll: i64,
+    pub bonus: i64,
+}
+
+impl EffectSave {
+    pub fn success(&self) -> bool {
+        self.roll + self.bonus >= self.dc
+    }
+}
+
+impl fmt::Display for EffectSave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} save {} ({:+}) = {} vs DC {}, {}",
+            self.ability,
+            self.roll,
+            self.bonus,
+            self.roll + self.bonus,
+            self.dc,
+            if self.success() { "success" } else { "failure" }
+        )
+    }
+}
+
+/// What happened when an effect fired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectOutcome {
+    pub creature: String,
+    pub effect: String,
+    pub roll: Option<DiceRoll>,
+    pub healing: bool,
+    /// The change of HP, negative for damage.
+    pub change: i64,
+    pub suppressed: bool,
+    pub save: Option<EffectSave>,
+}
+
+impl EffectOutcome {
+    /// Whether the effect ended with a successful save.
+    pub fn ended(&self) -> bool {
+        self.save.as_ref().is_some_and(|save| save.success())
+    }
+}
+
+impl fmt::Display for EffectOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.creature, self.effect)?;
+        let mut parts = Vec::new();
+        if self.suppressed {
+            parts.push("suppressed".to_string());
+        } else if let Some(roll) = &self.roll {
+            let mut part = if self.healing {
+                format!("heals {}", self.change)
+            } else {
+                format!("takes {} damage", self.change.unsigned_abs())
+            };
+            if !roll.rolls.is_empty() {
+                part.push_str(&format!(" ({roll})"));
+            }
+            parts.push(part);
+        }
+        if let Some(save) = &self.save {
+            parts.push(save.to_string());
+        }
+        if self.ended() {
+            parts.push("ends".to_string());
+        }
+        if !parts.is_empty() {
+            write!(f, " {}", parts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Fire the effects of the creature with the trigger, applying the HP
+/// changes and removing the effects that were saved against.
+pub fn trigger_effects(creature: &mut CreatureItem, trigger: Trigger) -> Vec<EffectOutcome> {
+    trigger_effects_with(creature, trigger, &mut rand::rng())
+}
+
+pub fn trigger_effects_with<R: Rng>(
+    creature: &mut CreatureItem,
+    trigger: Trigger,
+    rng: &mut R,
+) -> Vec<EffectOutcome> {
+    let mut outcomes = Vec::new();
+    let mut effects = std::mem::take(&mut creature.effects);
+    effects.retain_mut(|effect| {
+        if effect.trigger != trigger {
+            return true;
+        }
+        let mut outcome = EffectOutcome {
+            creature: creature.name.clone(),
+            effect: effect.name.clone(),
+            roll: None,
+            healing: false,
+            change: 0,
+            suppressed: false,
+            save: None,
+        };
+
+        if let Some(hit_points) = &effect.hit_points {
+            if hit_points.healing && effect.suppressed {
+                outcome.suppressed = true;
+            } else {
+                let roll = hit_points.dice.roll_with(rng);
+                let amount = roll.total.max(0) as u64;
+                let before = creature.hit_points as i64;
+                if hit_points.healing {
+                    creature.heal(amount);
+                } else {
+                    let defense = hit_points
+                        .damage_type
+                        .as_deref()
+                        .and_then(|damage_type| defense_against(creature, damage_type));
+                    creature.take_damage(apply_defense(amount, defense));
+                }
+                outcome.healing = hit_points.healing;
+                outcome.change = creature.hit_points as i64 - before;
+                outcome.roll = Some(roll);
+            }
+        }
+        effect.suppressed = false;
+
+        if let Some((ability, dc)) = effect.save {
+            outcome.save = Some(EffectSave {
+                ability,
+                dc,
+                roll: rng.random_range(1..=20),
+                bonus: ability.save_bonus(creature),
+            });
+        }
+        let ended = outcome.ended();
+        outcomes.push(outcome);
+        !ended
+    });
+    creature.effects = effects;
+    outcomes
+}
+
+/// Damage of the type stops the healing of the effects it suppresses.
+pub fn suppress_effects(creature: &mut CreatureItem, damage_type: &str) {
+    for effect in creature.effects.iter_mut() {
+        if effect.suppressed_by(damage_type) {
+            effect.suppressed = true;
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::control::EncounterState;
+use serde::{Deserialize, Serialize};
+
+/// Refresh interval of the player page in seconds.
+const REFRESH_SECONDS: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerViewRow {
+    pub name: String,
+    /// "12/30" for players, empty when their HP is not tracked, and a
+    /// descriptor like "Bloodied" for NPCs.
+    pub health: String,
+    pub conditions: Vec<String>,
+    pub current: bool,
+}
+
+/// The initiative order as the players may see it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub round: u32,
+    pub creatures: Vec<PlayerViewRow>,
+}
+
+impl PlayerView {
+    pub fn new(state: &EncounterState) -> Self {
+        let creatures = state
+            .creatures
+            .iter()
+            .filter(|creature| !creature.hidden)
+            .map(|creature| PlayerViewRow {
+                name: creature.name.clone(),
+                health: if creature.untracked_hp {
+                    String::new()
+                } else if creature.player {
+                    format!("{}/{}", creature.hit_points, creature.max_hit_points)
+                } else {
+                    creature.descriptor.clone()
+                },
+                conditions: creature.conditions.clone(),
+                current: state.current == Some(creature.id),
+            })
+            .collect();
+        PlayerView {
+            round: state.round,
+            creatures,
+        }
+    }
+
+    /// A page that reloads itself, to keep open on a screen the players see.
+    pub fn to_html(&self) -> String {
+        let title = if self.round > 0 {
+            format!("Round {}", self.round)
+        } else {
+            "Waiting for combat".to_string()
+        };
+        let rows: String = self
+            .creatures
+            .iter()
+            .map(|row| {
+                format!(
+                    "<tr{}><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    if row.current {
+                        " class=\"current\""
+                    } else {
+                        ""
+                    },
+                    escape_html(&row.name),
+                    escape_html(&row.health),
+                    escape_html(&row.conditions.join(", ")),
+                )
+            })
+            .collect();
+        format!(
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<meta http-equiv=\"refresh\" content=\"{REFRESH_SECONDS}\">
+<title>Who's Turn Is It?</title>
+<style>
+body {{ background: #020617; color: #e2e8f0; font-family: sans-serif; font-size: 1.5em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+td, th {{ padding: 0.4em 0.8em; text-align: left; }}
+tr.current {{ background: #1e40af; font-weight: bold; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<table>
+<tr><th>Name</th><th>Health</th><th>Conditions</th></tr>
+{rows}</table>
+</body>
+</html>
+"
+        )
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,156 @@
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferenceKind {
+    #[default]
+    Condition,
+    Rule,
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferenceKind::Condition => write!(f, "Condition"),
+            ReferenceKind::Rule => write!(f, "Rule"),
+        }
+    }
+}
+
+/// An entry of the Open5e conditions or rules sections.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiReference {
+    pub name: String,
+    pub desc: String,
+    #[serde(skip)]
+    pub kind: ReferenceKind,
+}
+
+impl ApiReference {
+    pub fn condition(name: &str, desc: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            desc: desc.to_string(),
+            kind: ReferenceKind::Condition,
+        }
+    }
+}
+
+impl fmt::Display for ApiReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})\n\n{}", self.name, self.kind, self.desc)
+    }
+}
+
+/// The conditions of the SRD, used when Open5e can't be reached.
+pub const SRD_CONDITIONS: &[(&str, &str)] = &[
+    (
+        "Blinded",
+        "* A blinded creature can't see and automatically fails any ability check that requires sight.\n\
+         * Attack rolls against the creature have advantage, and the creature's attack rolls have disadvantage.",
+    ),
+    (
+        "Charmed",
+        "* A charmed creature can't attack the charmer or target the charmer with harmful abilities or magical effects.\n\
+         * The charmer has advantage on any ability check to interact socially with the creature.",
+    ),
+    (
+        "Deafened",
+        "* A deafened creature can't hear and automatically fails any ability check that requires hearing.",
+    ),
+    (
+        "Exhaustion",
+        "Exhaustion is measured in six levels. An effect can give a creature one or more levels of exhaustion.\n\
+         1. Disadvantage on ability checks\n\
+         2. Speed halved\n\
+         3. Disadvantage on attack rolls and saving throws\n\
+         4. Hit point maximum halved\n\
+         5. Speed reduced to 0\n\
+         6. Death\n\
+         Finishing a long rest reduces a creature's exhaustion level by 1, provided that the creature has also ingested some food and drink.",
+    ),
+    (
+        "Frightened",
+        "* A frightened creature has disadvantage on ability checks and attack rolls while the source of its fear is within line of sight.\n\
+         * The creature can't willingly move closer to the source of its fear.",
+    ),
+    (
+        "Grappled",
+        "* A grappled creature's speed becomes 0, and it can't benefit from any bonus to its speed.\n\
+         * The condition ends if the grappler is incapacitated.\n\
+         * The condition also ends if an effect removes the grappled creature from the reach of the grappler or grappling effect, such as when a creature is hurled away by the thunderwave spell.",
+    ),
+    (
+        "Incapacitated",
+        "* An incapacitated creature can't take actions or reactions.",
+    ),
+    (
+        "Invisible",
+        "* An invisible creature is impossible to see without the aid of magic or a special sense. For the purpose of hiding, the creature is heavily obscured. The creature's location can be detected by any noise it makes or any tracks it leaves.\n\
+         * Attack rolls against the creature have disadvantage, and the creature's attack rolls have advantage.",
+    ),
+    (
+        "Paralyzed",
+        "* A paralyzed creature is incapacitated and can't move or speak.\n\
+         * The creature automatically fails Strength and Dexterity saving throws.\n\
+         * Attack rolls against the creature have advantage.\n\
+         * Any attack that hits the creature is a critical hit if the attacker is within 5 feet of the creature.",
+    ),
+    (
+        "Petrified",
+        "* A petrified creature is transformed, along with any nonmagical object it is wearing or carrying, into a solid inanimate substance (usually stone). Its weight increases by a factor of ten, and it ceases aging.\n\
+         * The creature is incapacitated, can't move or speak, and is unaware of its surroundings.\n\
+         * Attack rolls against the creature have advantage.\n\
+         * The creature automatically fails Strength and Dexterity saving throws.\n\
+         * The creature has resistance to all damage.\n\
+         * The creature is immune to poison and disease, although a poison or disease already in its system is suspended, not neutralized.",
+    ),
+    (
+        "Poisoned",
+        "* A poisoned creature has disadvantage on attack rolls and ability checks.",
+    ),
+    (
+        "Prone",
+        "* A prone creature's only movement option is to crawl, unless it stands up and thereby ends the condition.\n\
+         * The creature has disadvantage on attack rolls.\n\
+         * An attack roll against the creature has advantage if the attacker is within 5 feet of the creature. Otherwise, the attack roll has disadvantage.",
+    ),
+    (
+        "Restrained",
+        "* A restrained creature's speed becomes 0, and it can't benefit from any bonus to its speed.\n\
+         * Attack rolls against the creature have advantage, and the creature's attack rolls have disadvantage.\n\
+         * The creature has disadvantage on Dexterity saving throws.",
+    ),
+    (
+        "Stunned",
+        "* A stunned creature is incapacitated, can't move, and can speak only falteringly.\n\
+         * The creature automatically fails Strength and Dexterity saving throws.\n\
+         * Attack rolls against the creature have advantage.",
+    ),
+    (
+        "Unconscious",
+        "* An unconscious creature is incapacitated, can't move or speak, and is unaware of its surroundings.\n\
+         * The creature drops whatever it's holding and falls prone.\n\
+         * The creature automatically fails Strength and Dexterity saving throws.\n\
+         * Attack rolls against the creature have advantage.\n\
+         * Any attack that hits the creature is a critical hit if the attacker is within 5 feet of the creature.",
+    ),
+];
+
+/// Search the bundled SRD conditions, an empty query gives all of them.
+pub fn offline_conditions(query: &str) -> Vec<ApiReference> {
+    let query = query.trim().to_lowercase();
+    SRD_CONDITIONS
+        .iter()
+        .filter(|(name, _)| name.to_lowercase().contains(&query))
+        .map(|(name, desc)| ApiReference::condition(name, desc))
+        .collect()
+}
+
+/// Look up a condition applied to a creature by its name.
+pub fn find_condition(name: &str) -> Option<ApiReference> {
+    SRD_CONDITIONS
+        .iter()
+        .find(|(condition, _)| condition.eq_ignore_ascii_case(name))
+        .map(|(name, desc)| ApiReference::condition(name, desc))
+}
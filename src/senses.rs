@@ -0,0 +1,144 @@
+use crate::checks::Skill;
+use crate::creature::{CreatureItem, CreatureList, Status};
+use std::cmp::Reverse;
+use std::fmt;
+
+/// Special senses and their ranges in feet, parsed from a stat block such as
+/// "darkvision 60 ft., tremorsense 30 ft., passive Perception 11".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Senses {
+    pub darkvision: Option<u32>,
+    pub blindsight: Option<u32>,
+    pub tremorsense: Option<u32>,
+    pub truesight: Option<u32>,
+}
+
+pub fn parse_senses(senses: &str) -> Senses {
+    let mut parsed = Senses::default();
+    for part in senses.split([',', ';']) {
+        let part = part.trim().to_lowercase();
+        let Some((sense, rest)) = part.split_once(' ') else {
+            continue;
+        };
+        let range = rest
+            .split_whitespace()
+            .next()
+            .and_then(|range| range.parse::<u32>().ok());
+        match sense {
+            "darkvision" => parsed.darkvision = range,
+            "blindsight" => parsed.blindsight = range,
+            "tremorsense" => parsed.tremorsense = range,
+            "truesight" => parsed.truesight = range,
+            _ => {}
+        }
+    }
+    parsed
+}
+
+/// The "passive Perception 13" of the senses, if it lists one.
+pub fn parse_passive_perception(senses: &str) -> Option<i64> {
+    senses.split([',', ';']).find_map(|part| {
+        let part = part.trim().to_lowercase();
+        part.strip_prefix("passive perception")?.trim().parse().ok()
+    })
+}
+
+/// The passive Perception of the stat block, or 10 plus the Perception bonus,
+/// which falls back to the Wisdom modifier, when it has none.
+pub fn passive_perception(creature: &CreatureItem) -> i64 {
+    creature
+        .senses
+        .as_deref()
+        .and_then(parse_passive_perception)
+        .unwrap_or_else(|| 10 + Skill::Perception.bonus(creature))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensesRow {
+    pub name: String,
+    pub passive_perception: i64,
+    pub senses: Senses,
+    pub languages: String,
+}
+
+impl SensesRow {
+    pub fn new(creature: &CreatureItem) -> Self {
+        Self {
+            name: creature.name.clone(),
+            passive_perception: passive_perception(creature),
+            senses: creature
+                .senses
+                .as_deref()
+                .map(parse_senses)
+                .unwrap_or_default(),
+            languages: creature.languages.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SensesColumn {
+    Name,
+    #[default]
+    PassivePerception,
+    Darkvision,
+    Blindsight,
+    Tremorsense,
+    Truesight,
+    Languages,
+}
+
+impl SensesColumn {
+    pub const ALL: [SensesColumn; 7] = [
+        SensesColumn::Name,
+        SensesColumn::PassivePerception,
+        SensesColumn::Darkvision,
+        SensesColumn::Blindsight,
+        SensesColumn::Tremorsense,
+        SensesColumn::Truesight,
+        SensesColumn::Languages,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = SensesColumn::ALL
+            .iter()
+            .position(|&c| c == self)
+            .unwrap_or(0);
+        SensesColumn::ALL[(i + 1) % SensesColumn::ALL.len()]
+    }
+}
+
+impl fmt::Display for SensesColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensesColumn::Name => write!(f, "Name"),
+            SensesColumn::PassivePerception => write!(f, "Passive"),
+            SensesColumn::Darkvision => write!(f, "Darkvision"),
+            SensesColumn::Blindsight => write!(f, "Blindsight"),
+            SensesColumn::Tremorsense => write!(f, "Tremorsense"),
+            SensesColumn::Truesight => write!(f, "Truesight"),
+            SensesColumn::Languages => write!(f, "Languages"),
+        }
+    }
+}
+
+/// The senses of every living creature, sorted by the column. Names and
+/// languages sort alphabetically, numbers with the highest first.
+pub fn senses_overview(creature_list: &CreatureList, column: SensesColumn) -> Vec<SensesRow> {
+    let mut rows: Vec<SensesRow> = creature_list
+        .items
+        .iter()
+        .filter(|creature| creature.status == Status::Alive)
+        .map(SensesRow::new)
+        .collect();
+    match column {
+        SensesColumn::Name => rows.sort_by(|a, b| a.name.cmp(&b.name)),
+        SensesColumn::PassivePerception => rows.sort_by_key(|row| Reverse(row.passive_perception)),
+        SensesColumn::Darkvision => rows.sort_by_key(|row| Reverse(row.senses.darkvision)),
+        SensesColumn::Blindsight => rows.sort_by_key(|row| Reverse(row.senses.blindsight)),
+        SensesColumn::Tremorsense => rows.sort_by_key(|row| Reverse(row.senses.tremorsense)),
+        SensesColumn::Truesight => rows.sort_by_key(|row| Reverse(row.senses.truesight)),
+        SensesColumn::Languages => rows.sort_by(|a, b| a.languages.cmp(&b.languages)),
+    }
+    rows
+}
@@ -0,0 +1,188 @@
+use crate::abilities::Rest;
+use serde::Deserialize;
+use std::fmt;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ApiSpell {
+    pub slug: Option<String>,
+    pub name: String,
+    pub desc: Option<String>,
+    pub higher_level: Option<String>,
+    pub range: Option<String>,
+    pub components: Option<String>,
+    pub material: Option<String>,
+    pub ritual: Option<String>,
+    pub duration: Option<String>,
+    pub concentration: Option<String>,
+    pub casting_time: Option<String>,
+    pub level: Option<String>,
+    pub level_int: Option<i64>,
+    pub school: Option<String>,
+    pub dnd_class: Option<String>,
+}
+
+impl fmt::Display for ApiSpell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        parts.push(format!("Name: {}", self.name));
+        if let Some(level) = &self.level {
+            parts.push(format!("Level: {level}"));
+        }
+        if let Some(school) = &self.school {
+            parts.push(format!("School: {school}"));
+        }
+        if let Some(casting_time) = &self.casting_time {
+            parts.push(format!("Casting Time: {casting_time}"));
+        }
+        if let Some(range) = &self.range {
+            parts.push(format!("Range: {range}"));
+        }
+        if let Some(components) = &self.components {
+            match self.material.as_ref().filter(|m| !m.is_empty()) {
+                Some(material) => parts.push(format!("Components: {components} ({material})")),
+                None => parts.push(format!("Components: {components}")),
+            }
+        }
+        if let Some(duration) = &self.duration {
+            if self.concentration.as_deref() == Some("yes") {
+                parts.push(format!("Duration: Concentration, {duration}"));
+            } else {
+                parts.push(format!("Duration: {duration}"));
+            }
+        }
+        if self.ritual.as_deref() == Some("yes") {
+            parts.push("Ritual: yes".to_string());
+        }
+        if let Some(desc) = &self.desc {
+            parts.push(format!("\n{desc}"));
+        }
+        if let Some(higher_level) = self.higher_level.as_ref().filter(|h| !h.is_empty()) {
+            parts.push(format!("\nAt Higher Levels: {higher_level}"));
+        }
+        write!(f, "{}", parts.join("\n"))
+    }
+}
+
+/// The slug of a spell in a monster's `spell_list`,
+/// e.g. "https://api.open5e.com/v1/spells/fire-bolt/" gives "fire-bolt".
+pub fn spell_slug_from_url(url: &str) -> Option<&str> {
+    url.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|slug| !slug.is_empty())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellSlot {
+    pub level: u8,
+    pub max: u32,
+    pub remaining: u32,
+    /// A warlock's pact slot, which comes back on a short rest.
+    pub pact: bool,
+}
+
+impl SpellSlot {
+    pub fn new(level: u8, max: u32) -> Self {
+        Self {
+            level,
+            max,
+            remaining: max,
+            pact: false,
+        }
+    }
+
+    pub fn pact(level: u8, max: u32) -> Self {
+        Self {
+            pact: true,
+            ..Self::new(level, max)
+        }
+    }
+
+    /// Spend one slot, returns false if there was nothing left to spend.
+    pub fn spend(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        true
+    }
+
+    pub fn restore(&mut self) {
+        if self.remaining < self.max {
+            self.remaining += 1;
+        }
+    }
+
+    pub fn rest(&mut self, rest: Rest) {
+        if rest == Rest::Long || self.pact {
+            self.remaining = self.max;
+        }
+    }
+}
+
+impl fmt::Display for SpellSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} level{}: {}/{}",
+            ordinal(self.level),
+            if self.pact { " (pact)" } else { "" },
+            self.remaining,
+            self.max
+        )
+    }
+}
+
+pub fn ordinal(n: u8) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// Find the spell slots in the text of a "Spellcasting" special ability,
+/// e.g. "1st level (4 slots): mage armor" or, for warlocks,
+/// "1st-5th level (3 5th-level slots): ...".
+pub fn parse_spell_slots(desc: &str) -> Vec<SpellSlot> {
+    let mut slots: Vec<SpellSlot> = desc.lines().filter_map(parse_spell_slot_line).collect();
+    slots.sort_by_key(|slot| slot.level);
+    slots.dedup_by_key(|slot| slot.level);
+    slots
+}
+
+fn parse_spell_slot_line(line: &str) -> Option<SpellSlot> {
+    let (head, _) = line.split_once(':')?;
+    let head = head.trim().to_lowercase();
+    let open = head.find('(')?;
+    let close = head[open..].find(')')? + open;
+    let inner = &head[open + 1..close];
+    if !inner.contains("slot") {
+        return None;
+    }
+
+    let mut words = inner.split_whitespace();
+    let count: u32 = words.next()?.parse().ok()?;
+    // Only warlocks name the level of their slots, e.g. "3 5th-level slots"
+    let (level, pact) = match words.next() {
+        Some(word) if word.ends_with("-level") => (leading_number(word)?, true),
+        _ => (leading_number(head[..open].trim())?, false),
+    };
+    if level == 0 {
+        return None;
+    }
+    Some(if pact {
+        SpellSlot::pact(level, count)
+    } else {
+        SpellSlot::new(level, count)
+    })
+}
+
+/// The number at the start of "1st", "5th-level" or "1st level".
+fn leading_number(s: &str) -> Option<u8> {
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
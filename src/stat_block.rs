@@ -0,0 +1,394 @@
+use crate::checks::ability_modifier;
+use crate::creature::{ApiCreatureSearchItem, CreatureItem};
+use crate::difficulty::xp_for;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+
+const ACTION_SEPARATOR: &str = "\n--------------------------------\n";
+/// The sections made of several actions, an array of them in JSON.
+const ACTION_SECTIONS: [&str; 4] = [
+    "Actions",
+    "Legendary Actions",
+    "Reactions",
+    "Special Abilities",
+];
+
+#[derive(PartialEq)]
+pub enum TextFormatting {
+    Line,
+    NewLine,
+}
+
+/// A field of a stat block: its name, its value and how it is laid out.
+pub type StatBlockLine = (String, String, TextFormatting);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatBlockFormat {
+    #[default]
+    Text,
+    Markdown,
+    Json,
+}
+
+impl fmt::Display for StatBlockFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatBlockFormat::Text => write!(f, "text"),
+            StatBlockFormat::Markdown => write!(f, "markdown"),
+            StatBlockFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl FromStr for StatBlockFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(StatBlockFormat::Text),
+            "markdown" | "md" => Ok(StatBlockFormat::Markdown),
+            "json" => Ok(StatBlockFormat::Json),
+            _ => Err(format!("Unknown format: {s}")),
+        }
+    }
+}
+
+pub fn render_stat_block(creature: &CreatureItem, format: StatBlockFormat) -> String {
+    let lines = npc_info(creature, None);
+    match format {
+        StatBlockFormat::Text => to_text(&lines),
+        StatBlockFormat::Markdown => to_markdown(&lines),
+        StatBlockFormat::Json => serde_json::to_string_pretty(&to_json(&lines)).unwrap_or_default(),
+    }
+}
+
+/// Several stat blocks one after another, always a JSON array for `Json`.
+pub fn render_stat_blocks(creatures: &[CreatureItem], format: StatBlockFormat) -> String {
+    match format {
+        StatBlockFormat::Json => {
+            let values: Vec<Value> = creatures
+                .iter()
+                .map(|c| to_json(&npc_info(c, None)))
+                .collect();
+            serde_json::to_string_pretty(&values).unwrap_or_default()
+        }
+        _ => creatures
+            .iter()
+            .map(|c| render_stat_block(c, format))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// The stat block of a search result, as it would be added to the encounter.
+pub fn render_search_item(item: &ApiCreatureSearchItem, format: StatBlockFormat) -> String {
+    render_stat_block(&CreatureItem::new_npc(item), format)
+}
+
+/// Short fields as a list and long ones as sections under a heading.
+pub fn to_markdown(lines: &[StatBlockLine]) -> String {
+    let mut out = Vec::new();
+    let mut list = Vec::new();
+    for (k, v, f) in lines {
+        match f {
+            TextFormatting::Line if k == "Name" => out.push(format!("# {v}")),
+            TextFormatting::Line => list.push(format!("- **{k}:** {v}")),
+            TextFormatting::NewLine => {
+                if !list.is_empty() {
+                    out.push(list.join("\n"));
+                    list.clear();
+                }
+                let v = v.replace(ACTION_SEPARATOR, "\n\n---\n\n");
+                out.push(format!("## {k}\n\n{v}"));
+            }
+        }
+    }
+    if !list.is_empty() {
+        out.push(list.join("\n"));
+    }
+    out.join("\n\n")
+}
+
+/// An object with the field names in snake_case, e.g. `challenge_rating`.
+pub fn to_json(lines: &[StatBlockLine]) -> Value {
+    let mut map = Map::new();
+    for (k, v, f) in lines {
+        let key = k.to_lowercase().replace(' ', "_");
+        let value = if *f == TextFormatting::NewLine && ACTION_SECTIONS.contains(&k.as_str()) {
+            Value::Array(
+                v.split(ACTION_SEPARATOR)
+                    .map(|s| Value::String(s.to_string()))
+                    .collect(),
+            )
+        } else {
+            Value::String(v.clone())
+        };
+        map.insert(key, value);
+    }
+    Value::Object(map)
+}
+
+/// The stat block as it is shown in "Creature Info".
+pub fn to_text(lines: &[StatBlockLine]) -> String {
+    lines
+        .iter()
+        .map(|(k, v, f)| {
+            if *f == TextFormatting::Line {
+                format!("{k}: {v}")
+            } else {
+                format!("\n==={k}===\n{v}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The fields of an NPC, with the pending HP change while it is edited.
+pub fn npc_info(c: &CreatureItem, health_change: Option<i64>) -> Vec<StatBlockLine> {
+    let mut lines = Vec::new();
+
+    let initiative = if let Some(val) = c.initiative {
+        val.to_string()
+    } else {
+        "Not set yet".to_string()
+    };
+    lines.push(("Initiative".to_string(), initiative, TextFormatting::Line));
+    lines.push(("Name".to_string(), c.name.clone(), TextFormatting::Line));
+
+    let mut hp_str = c.hit_points.to_string();
+    if let Some(health_change) = health_change {
+        hp_str.push_str(&format!(" ({health_change})"));
+    }
+    lines.push(("HP".to_string(), hp_str, TextFormatting::Line));
+    if !c.conditions.is_empty() {
+        lines.push((
+            "Conditions".to_string(),
+            c.conditions.join(", "),
+            TextFormatting::Line,
+        ));
+    }
+    if !c.effects.is_empty() {
+        let effects: Vec<String> = c.effects.iter().map(ToString::to_string).collect();
+        lines.push((
+            "Effects".to_string(),
+            effects.join("; "),
+            TextFormatting::Line,
+        ));
+    }
+
+    if let Some(ac) = c.armor_class {
+        lines.push(("AC".to_string(), ac.to_string(), TextFormatting::Line));
+    }
+    if let Some(val) = c.strength {
+        lines.push((
+            "Strength".to_string(),
+            format!("{val} ({:+})", ability_modifier(val)),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.strength_save {
+        lines.push((
+            "Strength Save".to_string(),
+            val.to_string(),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.dexterity {
+        lines.push((
+            "Dexterity".to_string(),
+            format!("{val} ({:+})", ability_modifier(val)),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.dexterity_save {
+        lines.push((
+            "Dexterity Save".to_string(),
+            val.to_string(),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.constitution {
+        lines.push((
+            "Constitution".to_string(),
+            format!("{val} ({:+})", ability_modifier(val)),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.constitution_save {
+        lines.push((
+            "Constitution Save".to_string(),
+            val.to_string(),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.intelligence {
+        lines.push((
+            "Intelligence".to_string(),
+            format!("{val} ({:+})", ability_modifier(val)),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.intelligence_save {
+        lines.push((
+            "Intelligence Save".to_string(),
+            val.to_string(),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.wisdom {
+        lines.push((
+            "Wisdom".to_string(),
+            format!("{val} ({:+})", ability_modifier(val)),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.wisdom_save {
+        lines.push((
+            "Wisdom Save".to_string(),
+            val.to_string(),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.charisma {
+        lines.push((
+            "Charisma".to_string(),
+            format!("{val} ({:+})", ability_modifier(val)),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(val) = c.charisma_save {
+        lines.push((
+            "Charisma Save".to_string(),
+            val.to_string(),
+            TextFormatting::Line,
+        ));
+    }
+    if let Some(speed) = &c.speed {
+        lines.push((
+            "Speed".to_string(),
+            format!("{speed}"),
+            TextFormatting::NewLine,
+        ));
+    }
+    if let Some(size) = &c.size {
+        lines.push(("Size".to_string(), size.clone(), TextFormatting::NewLine));
+    }
+    if let Some(skills) = &c.skills {
+        lines.push((
+            "Skills".to_string(),
+            skills.to_string(),
+            TextFormatting::NewLine,
+        ));
+    }
+    if let Some(val) = &c.damage_vulnerabilities {
+        let s = val.to_string();
+        if !s.is_empty() {
+            lines.push((
+                "Damage Vulnerabilities".to_string(),
+                s,
+                TextFormatting::NewLine,
+            ));
+        }
+    }
+    if let Some(val) = &c.damage_resistances {
+        let s = val.to_string();
+        if !s.is_empty() {
+            lines.push(("Damage Resistance".to_string(), s, TextFormatting::NewLine));
+        }
+    }
+    if let Some(val) = &c.damage_immunities {
+        let s = val.to_string();
+        if !s.is_empty() {
+            lines.push(("Damage Immunities".to_string(), s, TextFormatting::NewLine));
+        }
+    }
+    if let Some(val) = &c.condition_immunities {
+        let s = val.to_string();
+        if !s.is_empty() {
+            lines.push((
+                "Condition Immunities".to_string(),
+                s,
+                TextFormatting::NewLine,
+            ));
+        }
+    }
+    if let Some(val) = &c.senses {
+        let s = val.to_string();
+        if !s.is_empty() {
+            lines.push(("Senses".to_string(), s, TextFormatting::NewLine));
+        }
+    }
+    if let Some(val) = &c.languages {
+        let s = val.to_string();
+        if !s.is_empty() {
+            lines.push(("Languages".to_string(), s, TextFormatting::NewLine));
+        }
+    }
+    if let Some(val) = &c.challenge_rating {
+        let s = val.to_string();
+        if !s.is_empty() {
+            let s = match xp_for(&s) {
+                Some(xp) => format!("{s} ({xp} XP)"),
+                None => s,
+            };
+            lines.push(("Challenge Rating".to_string(), s, TextFormatting::NewLine));
+        }
+    }
+    if let Some(actions) = &c.actions {
+        lines.push((
+            "Actions".to_string(),
+            actions
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(ACTION_SEPARATOR),
+            TextFormatting::NewLine,
+        ));
+    }
+    if let Some(legendary_actions) = &c.legendary_actions {
+        lines.push((
+            "Legendary Actions".to_string(),
+            legendary_actions
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(ACTION_SEPARATOR),
+            TextFormatting::NewLine,
+        ));
+    }
+    if !c.spell_slots.is_empty() {
+        lines.push((
+            "Spell Slots".to_string(),
+            c.spell_slots
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            TextFormatting::NewLine,
+        ));
+    }
+    if let Some(reactions) = &c.reactions {
+        lines.push((
+            "Reactions".to_string(),
+            reactions
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(ACTION_SEPARATOR),
+            TextFormatting::NewLine,
+        ));
+    }
+    if let Some(special_abilities) = &c.special_abilities {
+        lines.push((
+            "Special Abilities".to_string(),
+            special_abilities
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(ACTION_SEPARATOR),
+            TextFormatting::NewLine,
+        ));
+    }
+    lines
+}
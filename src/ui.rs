@@ -1,7 +1,30 @@
-use crate::api::{search_for_creature, ApiError, MonsterSearch};
-use crate::creature::{ApiCreatureSearchItem, Faction};
-use crate::creature::{CreatureItem, CreatureList, Status};
+use crate::abilities::Rest;
+use crate::api::{
+    fetch_encounter_monsters, fetch_spell_list, search_for_creature, search_for_creatures_by_cr,
+    search_for_spell, search_reference, ApiError, EncounterMonsters, MonsterFilter,
+    MonsterFilterSearch, MonsterSearch, ReferenceSearch, SpellList, SpellSearch,
+};
+use crate::area_effect::{resolve_save, AreaEffect};
+use crate::builder::{suggest_encounters, xp_budget, EncounterPlan};
+use crate::checks::{ability_modifier, roll_check, Ability, Check, RollMode};
+use crate::combat_log::{get_logs_dir, CombatLog, EventKind, ExportFormat};
+use crate::control::{
+    serve, serve_players, ControlCommand, ControlRequest, EncounterState, Target,
+};
+use crate::creature::{creature_list_item, group_list_item, ApiCreatureSearchItem, Faction};
+use crate::creature::{CreatureItem, CreatureList};
+use crate::dice::{roll_d20, DiceExpr};
+use crate::difficulty::{encounter_summary, parse_challenge_rating, Difficulty};
+use crate::encounter::{get_encounters_dir, load_templates, EncounterTemplate, HpMode};
+use crate::history::{deletions, Command, Edit, History};
 use crate::keybindings::*;
+use crate::mob::{resolve_mob_attack, MobMode};
+use crate::ongoing::{parse_save, suppress_effects, trigger_effects, OngoingEffect, Trigger};
+use crate::reference::{find_condition, offline_conditions, ApiReference, ReferenceKind};
+use crate::senses::{senses_overview, SensesColumn};
+use crate::spells::ApiSpell;
+use crate::stat_block::{npc_info, to_text};
+use crate::zone::{expire_zones, zone_reminders, ZoneEffect};
 use color_eyre::Result;
 use ratatui::layout::Direction;
 use ratatui::text::Text;
@@ -16,13 +39,13 @@ use ratatui::{
     symbols,
     text::Line,
     widgets::{
-        Block, Borders, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph,
-        StatefulWidget, Widget, Wrap,
+        Block, Borders, Cell, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph, Row,
+        StatefulWidget, Table, Widget, Wrap,
     },
     DefaultTerminal,
 };
-use std::cmp::PartialEq;
 use std::time::Duration;
+use tokio::net::TcpListener;
 use tokio::sync::mpsc;
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
@@ -34,21 +57,80 @@ const ALT_ROW_BG_COLOR: Color = SLATE.c900;
 const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
 const TEXT_FG_COLOR: Color = SLATE.c200;
 
-#[derive(PartialEq)]
-enum TextFormatting {
-    Line,
-    NewLine,
-}
+/// The focus of the effect list in the ongoing effects popup, after its inputs.
+const EFFECT_LIST_FOCUS: usize = 5;
+/// The focus of the zone list in the zone effects popup, after its inputs.
+const ZONE_LIST_FOCUS: usize = 3;
 
 #[derive(Default)]
 pub struct App {
     creature_list: CreatureList,
+    history: History,
+    combat_log: CombatLog,
+    log_scroll: u16,
+    log_export_format: ExportFormat,
     should_exit: bool,
     show_creature_search_popup: bool,
     show_initiative_popup: bool,
     show_description_popup: bool,
+    show_ready_popup: bool,
+    show_mob_popup: bool,
+    show_roll_check_popup: bool,
+    show_senses_popup: bool,
+    show_difficulty_popup: bool,
+    show_builder_popup: bool,
+    show_template_popup: bool,
+    show_history_popup: bool,
+    show_log_popup: bool,
+    show_area_effect_popup: bool,
+    show_limited_uses_popup: bool,
+    show_resume_popup: bool,
+    show_effects_popup: bool,
+    show_zones_popup: bool,
+    show_spellcasting_popup: bool,
+    show_spell_search_popup: bool,
+    show_reference_popup: bool,
     initiative_input: Input,
     description_input: Input,
+    ready_input: Input,
+    mob_ac_input: Input,
+    mob_action_selected: usize,
+    mob_mode: MobMode,
+    mob_result: Option<String>,
+    roll_check_selected: usize,
+    roll_check_mode: RollMode,
+    roll_check_result: Option<String>,
+    senses_sort: SensesColumn,
+    builder_inputs: [Input; 6],
+    builder_focus: usize,
+    builder_difficulty: Option<Difficulty>,
+    builder_candidates: Vec<ApiCreatureSearchItem>,
+    builder_plans: Vec<EncounterPlan>,
+    builder_selected: usize,
+    builder_loading: bool,
+    builder_message: Option<String>,
+    builder_result_rx:
+        Option<mpsc::UnboundedReceiver<Result<Vec<ApiCreatureSearchItem>, ApiError>>>,
+    templates: Vec<EncounterTemplate>,
+    template_selected: Option<usize>,
+    template_name_input: Input,
+    template_loading: Option<EncounterTemplate>,
+    template_message: Option<String>,
+    template_result_rx: Option<mpsc::UnboundedReceiver<Result<EncounterMonsters, ApiError>>>,
+    area_effect_inputs: [Input; 4],
+    area_effect_focus: usize,
+    area_effect_no_half: bool,
+    area_effect_result: Vec<String>,
+    /// The ids of the creatures marked when the popup was opened.
+    area_effect_targets: Vec<u64>,
+    effect_inputs: [Input; 5],
+    effect_focus: usize,
+    effect_selected: usize,
+    effect_message: Option<String>,
+    zone_inputs: [Input; 3],
+    zone_focus: usize,
+    zone_selected: usize,
+    zone_message: Option<String>,
     creature_search_input: String,
     creature_search_result: Vec<ApiCreatureSearchItem>,
     creature_search_selected: Option<usize>,
@@ -59,6 +141,30 @@ pub struct App {
     health_change: i64,
     creature_info_scroll: u16,
     save_creature_viewing: Option<usize>,
+    limited_uses_selected: usize,
+    /// The delayed creature coming back and who it can act after, by id.
+    resume_id: u64,
+    resume_candidates: Vec<u64>,
+    resume_selected: usize,
+    status_message: Option<String>,
+    spell_slot_selected: usize,
+    spell_selected: Option<usize>,
+    spell_list_loading: bool,
+    spell_list_error: Option<String>,
+    /// The id of the creature the spell list is fetched for, with the result.
+    spell_list_result_rx: Option<mpsc::UnboundedReceiver<(u64, SpellList)>>,
+    spell_search_input: String,
+    spell_search_result: Vec<ApiSpell>,
+    spell_search_selected: Option<usize>,
+    spell_search_loading: bool,
+    spell_search_result_rx: Option<mpsc::UnboundedReceiver<Result<Vec<ApiSpell>, ApiError>>>,
+    reference_input: String,
+    reference_result: Vec<ApiReference>,
+    reference_selected: Option<usize>,
+    reference_loading: bool,
+    reference_result_rx: Option<mpsc::UnboundedReceiver<Result<Vec<ApiReference>, ApiError>>>,
+    control_rx: Option<mpsc::UnboundedReceiver<ControlRequest>>,
+    control_tx: Option<mpsc::UnboundedSender<ControlRequest>>,
 }
 
 impl App {
@@ -94,20 +200,189 @@ impl App {
                     }
                 }
             }
+
+            if let Some(rx) = &mut self.spell_search_result_rx {
+                if let Ok(result) = rx.try_recv() {
+                    self.spell_search_loading = false;
+                    self.spell_search_result_rx = None;
+                    match result {
+                        Ok(results) => {
+                            self.spell_search_result = results;
+                            self.spell_search_selected = if self.spell_search_result.is_empty() {
+                                None
+                            } else {
+                                Some(0)
+                            };
+                        }
+                        Err(_) => {
+                            self.spell_search_result.clear();
+                            self.spell_search_selected = None;
+                        }
+                    }
+                }
+            }
+
+            if let Some(rx) = &mut self.reference_result_rx {
+                if let Ok(result) = rx.try_recv() {
+                    self.reference_loading = false;
+                    self.reference_result_rx = None;
+                    self.reference_result = match result {
+                        Ok(results) => results,
+                        // Open5e can't be reached, use the bundled SRD conditions
+                        Err(_) => offline_conditions(&self.reference_input),
+                    };
+                    self.reference_selected = if self.reference_result.is_empty() {
+                        None
+                    } else {
+                        Some(0)
+                    };
+                }
+            }
+
+            if let Some(rx) = &mut self.builder_result_rx {
+                if let Ok(result) = rx.try_recv() {
+                    self.builder_loading = false;
+                    self.builder_result_rx = None;
+                    match result {
+                        Ok(candidates) => {
+                            self.builder_candidates = candidates;
+                            self.suggest_encounters();
+                        }
+                        Err(e) => self.builder_message = Some(e.to_string()),
+                    }
+                }
+            }
+
+            if let Some(rx) = &mut self.template_result_rx {
+                if let Ok(result) = rx.try_recv() {
+                    self.template_result_rx = None;
+                    let template = self.template_loading.take().unwrap_or_default();
+                    match result {
+                        Ok(monsters) => self.load_encounter(&template, monsters),
+                        Err(e) => {
+                            self.template_message = Some(e.to_string());
+                            self.status_message = Some(e.to_string());
+                        }
+                    }
+                }
+            }
+
+            let requests: Vec<ControlRequest> = match &mut self.control_rx {
+                Some(rx) => std::iter::from_fn(|| rx.try_recv().ok()).collect(),
+                None => Vec::new(),
+            };
+            for request in requests {
+                self.handle_control(request);
+            }
+
+            if let Some(rx) = &mut self.spell_list_result_rx {
+                if let Ok(result) = rx.try_recv() {
+                    self.spell_list_loading = false;
+                    self.spell_list_result_rx = None;
+                    let (id, spell_list) = result;
+                    if let Some(e) = spell_list.errors.first() {
+                        self.spell_list_error = Some(format!(
+                            "{} of the spells failed to load: {e}",
+                            spell_list.errors.len()
+                        ));
+                    }
+                    if let Some(i) = self.creature_list.position(id) {
+                        self.creature_list.items[i].spells = spell_list.spells;
+                    }
+                }
+            }
         }
         Ok(())
     }
 
-    async fn handle_key(&mut self, key: KeyEvent) {
+    /// Take commands from the control API while the tracker runs.
+    pub fn serve_control(&mut self, listener: TcpListener) {
+        tokio::spawn(serve(listener, self.control_sender()));
+    }
+
+    /// Show the player view while the tracker runs.
+    pub fn serve_player_view(&mut self, listener: TcpListener) {
+        tokio::spawn(serve_players(listener, self.control_sender()));
+    }
+
+    /// Both listeners pass their requests on through the same channel.
+    fn control_sender(&mut self) -> mpsc::UnboundedSender<ControlRequest> {
+        if let Some(tx) = &self.control_tx {
+            return tx.clone();
+        }
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.control_rx = Some(rx);
+        self.control_tx = Some(tx.clone());
+        tx
+    }
+
+    /// Apply a command of the control API the same way as its key.
+    pub fn handle_control(&mut self, request: ControlRequest) {
+        let result = match request.command {
+            ControlCommand::State => Ok(()),
+            ControlCommand::NextTurn => {
+                self.status_message = None;
+                // Stop peeking so the turn actually passes
+                if let Some(i) = self.save_creature_viewing.take() {
+                    self.creature_list.state.select(Some(i));
+                }
+                self.select_next();
+                Ok(())
+            }
+            ControlCommand::Damage(change) => self
+                .control_target(change.target.as_ref())
+                .map(|i| self.damage(i, change.amount)),
+            ControlCommand::Heal(change) => self
+                .control_target(change.target.as_ref())
+                .map(|i| self.heal(i, change.amount)),
+            ControlCommand::AddCreature(new_creature) => {
+                self.add_creature(new_creature.to_creature());
+                Ok(())
+            }
+        };
+        let _ = request
+            .reply
+            .send(result.map(|()| EncounterState::new(&self.creature_list, self.turn_holder())));
+    }
+
+    /// The creature whose turn it is, also while peeking at another one.
+    fn turn_holder(&self) -> Option<usize> {
+        self.save_creature_viewing
+            .or(self.creature_list.state.selected())
+    }
+
+    fn control_target(&self, target: Option<&Target>) -> Result<usize, String> {
+        match target {
+            Some(target) => target
+                .position(&self.creature_list)
+                .ok_or_else(|| format!("No creature with {target}")),
+            None => self
+                .turn_holder()
+                .ok_or_else(|| "No creature has the turn".to_string()),
+        }
+    }
+
+    pub async fn handle_key(&mut self, key: KeyEvent) {
         if key.kind != KeyEventKind::Press {
             return;
         }
+        self.status_message = None;
 
         if self.show_creature_search_popup {
             self.handle_creature_search_input(&key).await;
             return;
         }
 
+        if self.show_spell_search_popup {
+            self.handle_spell_search_input(&key);
+            return;
+        }
+
+        if self.show_reference_popup {
+            self.handle_reference_input(&key);
+            return;
+        }
+
         if self.show_initiative_popup {
             self.handle_initiative_input(&key);
             return;
@@ -118,6 +393,101 @@ impl App {
             return;
         }
 
+        if self.show_ready_popup {
+            self.handle_ready_input(&key);
+            return;
+        }
+
+        if self.show_mob_popup {
+            self.handle_mob_input(&key);
+            return;
+        }
+
+        if self.show_roll_check_popup {
+            self.handle_roll_check_input(&key);
+            return;
+        }
+
+        if self.show_builder_popup {
+            self.handle_builder_input(&key);
+            return;
+        }
+
+        if self.show_template_popup {
+            self.handle_template_input(&key);
+            return;
+        }
+
+        if self.show_log_popup {
+            self.handle_log_input(&key);
+            return;
+        }
+
+        if self.show_history_popup {
+            let keymap: KeyBindings = KeyBindings::default();
+            match key.code {
+                KeyCode::Char(c) if c == keymap.undo => self.undo(),
+                KeyCode::Char(c) if c == keymap.redo => self.redo(),
+                KeyCode::Esc | KeyCode::Enter => self.show_history_popup = false,
+                KeyCode::Char(c) if c == keymap.quit_app => self.show_history_popup = false,
+                _ => {}
+            }
+            return;
+        }
+
+        if self.show_difficulty_popup {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => self.show_difficulty_popup = false,
+                KeyCode::Char(c) if c == KeyBindings::default().quit_app => {
+                    self.show_difficulty_popup = false;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.show_senses_popup {
+            match key.code {
+                KeyCode::Tab => self.senses_sort = self.senses_sort.next(),
+                KeyCode::Esc | KeyCode::Enter => self.show_senses_popup = false,
+                KeyCode::Char(c) if c == KeyBindings::default().quit_app => {
+                    self.show_senses_popup = false;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.show_area_effect_popup {
+            self.handle_area_effect_input(&key);
+            return;
+        }
+
+        if self.show_limited_uses_popup {
+            self.handle_limited_uses_input(&key);
+            return;
+        }
+
+        if self.show_resume_popup {
+            self.handle_resume_input(&key);
+            return;
+        }
+
+        if self.show_effects_popup {
+            self.handle_effects_input(&key);
+            return;
+        }
+
+        if self.show_zones_popup {
+            self.handle_zones_input(&key);
+            return;
+        }
+
+        if self.show_spellcasting_popup {
+            self.handle_spellcasting_input(&key);
+            return;
+        }
+
         self.handle_general_input(&key);
     }
 
@@ -144,28 +514,132 @@ impl App {
                 self.creature_search_selected = None;
                 self.show_creature_search_popup = true;
             }
+            KeyCode::Char(c) if c == keymap.search_for_spell => {
+                self.spell_search_selected = None;
+                self.show_spell_search_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.search_reference => self.open_reference(),
+            KeyCode::Char(c) if c == keymap.show_conditions => self.open_creature_conditions(),
             KeyCode::Char(c) if c == keymap.insert_new_player => self.insert_new(),
             KeyCode::Char(c) if c == keymap.delete_creature => self.delete_creature(),
             KeyCode::Char(c) if c == keymap.new_encounter => self.new_encounter(),
-            KeyCode::Char(c) if c == keymap.new_encounter => {
-                if self.creature_list.state.selected().is_some() {
-                    self.show_initiative_popup = true;
+            KeyCode::Char(c)
+                if c == keymap.set_initiative && self.creature_list.state.selected().is_some() =>
+            {
+                self.show_initiative_popup = true;
+            }
+            KeyCode::Char(c)
+                if c == keymap.set_creature_description
+                    && self.creature_list.state.selected().is_some() =>
+            {
+                self.show_description_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.duplicate_creature => self.duplicate_creature(),
+            KeyCode::Char(c) if c == keymap.delay_turn => self.delay_turn(),
+            KeyCode::Char(c) if c == keymap.start_combat => self.start_combat(),
+            KeyCode::Char(c) if c == keymap.mob_attack => self.open_mob_attack(),
+            KeyCode::Char(c) if c == keymap.show_senses => self.show_senses_popup = true,
+            KeyCode::Char(c) if c == keymap.show_difficulty => self.show_difficulty_popup = true,
+            KeyCode::Char(c) if c == keymap.encounter_builder => self.open_builder(),
+            KeyCode::Char(c) if c == keymap.encounter_templates => self.open_templates(),
+            KeyCode::Char(c) if c == keymap.undo => self.undo(),
+            KeyCode::Char(c) if c == keymap.redo => self.redo(),
+            KeyCode::Char(c) if c == keymap.show_history => self.show_history_popup = true,
+            KeyCode::Char(c) if c == keymap.show_log => {
+                self.log_scroll = 0;
+                self.show_log_popup = true;
+            }
+            KeyCode::Char(c)
+                if c == keymap.roll_check && self.creature_list.state.selected().is_some() =>
+            {
+                self.roll_check_result = None;
+                self.show_roll_check_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.toggle_hidden => self.toggle_hidden(),
+            KeyCode::Char(c) if c == keymap.mark_creature => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    let creature = &mut self.creature_list.items[i];
+                    creature.marked = !creature.marked;
                 }
             }
-            KeyCode::Char(c) if c == keymap.set_creature_description => {
-                if self.creature_list.state.selected().is_some() {
-                    self.show_description_popup = true;
+            KeyCode::Char(c)
+                if c == keymap.area_effect && !self.creature_list.targets().is_empty() =>
+            {
+                self.area_effect_focus = 0;
+                self.area_effect_result.clear();
+                self.area_effect_targets = self
+                    .creature_list
+                    .targets()
+                    .into_iter()
+                    .map(|i| self.creature_list.items[i].id)
+                    .collect();
+                self.show_area_effect_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.toggle_group_initiative => {
+                self.creature_list.toggle_group_initiative();
+            }
+            KeyCode::Char(c) if c == keymap.toggle_group_collapsed => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    self.creature_list.toggle_collapsed(i);
                 }
             }
-            KeyCode::Char(c) if c == keymap.duplicate_creature => self.duplicate_creature(),
+            KeyCode::Char(c) if c == keymap.toggle_surprised => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    let creature = &mut self.creature_list.items[i];
+                    creature.surprised = !creature.surprised;
+                }
+            }
+            KeyCode::Char(c)
+                if c == keymap.ready_action && self.creature_list.state.selected().is_some() =>
+            {
+                self.show_ready_popup = true;
+            }
+            KeyCode::Char(c)
+                if c == keymap.show_limited_uses
+                    && self.creature_list.state.selected().is_some() =>
+            {
+                self.limited_uses_selected = 0;
+                self.show_limited_uses_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.show_spellcasting => self.open_spellcasting(),
+            KeyCode::Char(c)
+                if c == keymap.ongoing_effects && self.creature_list.state.selected().is_some() =>
+            {
+                self.effect_focus = 0;
+                self.effect_selected = 0;
+                self.effect_message = None;
+                self.show_effects_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.zone_effects => {
+                self.zone_focus = 0;
+                self.zone_selected = 0;
+                self.zone_message = None;
+                self.show_zones_popup = true;
+            }
+            KeyCode::Char(c) if c == keymap.toggle_action => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    let economy = &mut self.creature_list.items[i].economy;
+                    economy.action = !economy.action;
+                }
+            }
+            KeyCode::Char(c) if c == keymap.toggle_bonus_action => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    let economy = &mut self.creature_list.items[i].economy;
+                    economy.bonus_action = !economy.bonus_action;
+                }
+            }
+            KeyCode::Char(c) if c == keymap.toggle_reaction => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    let economy = &mut self.creature_list.items[i].economy;
+                    economy.reaction = !economy.reaction;
+                }
+            }
+            KeyCode::Char(c) if c == keymap.short_rest => self.creature_list.rest(Rest::Short),
+            KeyCode::Char(c) if c == keymap.long_rest => self.creature_list.rest(Rest::Long),
 
             // Scroll creature info
             KeyCode::Down => self.creature_info_scroll += 1,
-            KeyCode::Up => {
-                if self.creature_info_scroll > 0 {
-                    self.creature_info_scroll -= 1;
-                }
-            }
+            KeyCode::Up if self.creature_info_scroll > 0 => self.creature_info_scroll -= 1,
             _ => {}
         }
     }
@@ -173,8 +647,16 @@ impl App {
     fn duplicate_creature(&mut self) {
         if let Some(i) = self.creature_list.state.selected() {
             if let Some(mut creature) = self.creature_list.items.get(i).cloned() {
-                let initiative_modifier: i64 = (creature.dexterity.unwrap_or(0) - 10) / 2;
-                creature.initiative = Some(rand::random_range(1..21) + initiative_modifier);
+                // Creatures in a group keep the shared initiative roll
+                if self.creature_list.group_of(i).is_none() {
+                    let initiative_modifier: i64 =
+                        ability_modifier(creature.dexterity.unwrap_or(0));
+                    creature.initiative = Some(rand::random_range(1..21) + initiative_modifier);
+                }
+                creature.id = self.creature_list.next_id();
+                self.history.record(Command::Insert {
+                    creature: creature.clone(),
+                });
                 self.creature_list.items.insert(i + 1, creature);
                 self.creature_list.state.select(Some(i + 1));
             }
@@ -187,8 +669,16 @@ impl App {
             KeyCode::Enter => {
                 if let Some(i) = self.creature_list.state.selected() {
                     if let Ok(new_initiative) = self.initiative_input.value().parse::<i64>() {
-                        self.creature_list.items[i].initiative = Some(new_initiative);
-                        self.creature_list.sort_creature_list();
+                        let creature = &self.creature_list.items[i];
+                        self.history.record(Command::Edit {
+                            id: creature.id,
+                            name: creature.name.clone(),
+                            edit: Edit::Initiative {
+                                before: creature.initiative,
+                                after: Some(new_initiative),
+                            },
+                        });
+                        self.creature_list.set_initiative(i, Some(new_initiative));
                     }
                 }
                 self.show_initiative_popup = false;
@@ -209,7 +699,16 @@ impl App {
             KeyCode::Enter => {
                 if let Some(i) = self.creature_list.state.selected() {
                     let new_description = self.description_input.value().to_string();
-                    self.creature_list.items[i].desc = Some(new_description);
+                    let creature = &mut self.creature_list.items[i];
+                    self.history.record(Command::Edit {
+                        id: creature.id,
+                        name: creature.name.clone(),
+                        edit: Edit::Description {
+                            before: creature.desc.clone(),
+                            after: Some(new_description.clone()),
+                        },
+                    });
+                    creature.desc = Some(new_description);
                 }
                 self.show_description_popup = false;
                 self.description_input = Input::default();
@@ -224,262 +723,2463 @@ impl App {
         }
     }
 
-    async fn handle_creature_search_input(&mut self, key: &KeyEvent) {
+    fn handle_limited_uses_input(&mut self, key: &KeyEvent) {
         let keymap: KeyBindings = KeyBindings::default();
+        let Some(i) = self.creature_list.state.selected() else {
+            self.show_limited_uses_popup = false;
+            return;
+        };
+        let mut limited_uses = self.creature_list.items[i].limited_uses_mut();
         match key.code {
-            KeyCode::Tab => {
-                if !self.creature_search_result.is_empty() {
-                    if self.creature_search_selected.is_some() {
-                        self.creature_search_selected = None;
-                    } else {
-                        self.creature_search_selected = Some(0);
-                    }
-                } else {
-                    self.creature_search_selected = None;
-                }
+            KeyCode::Esc | KeyCode::Enter => self.show_limited_uses_popup = false,
+            KeyCode::Char(c) if c == keymap.quit_app => self.show_limited_uses_popup = false,
+            KeyCode::Char(c)
+                if c == keymap.move_down && self.limited_uses_selected + 1 < limited_uses.len() =>
+            {
+                self.limited_uses_selected += 1;
             }
-            KeyCode::Esc => {
-                self.show_creature_search_popup = false;
-            }
-            KeyCode::Backspace => {
-                self.creature_search_input.pop();
+            KeyCode::Char(c) if c == keymap.move_up => {
+                self.limited_uses_selected = self.limited_uses_selected.saturating_sub(1);
             }
-            KeyCode::Enter => {
-                if let Some(selected) = self.creature_search_selected {
-                    if let Some(selected_creature) = self.creature_search_result.get(selected) {
-                        self.creature_list
-                            .add_new_creature(CreatureItem::new_npc(selected_creature));
-                        self.show_creature_search_popup = false;
-                        return;
-                    }
+            KeyCode::Char(c) if c == keymap.lower_health => {
+                if let Some(limited_use) = limited_uses.get_mut(self.limited_uses_selected) {
+                    limited_use.spend();
                 }
-
-                self.creature_search_loading = true;
-                let (tx, rx) = mpsc::unbounded_channel();
-                self.creature_search_result_rx = Some(rx);
-                let input = self.creature_search_input.clone();
-                tokio::spawn(async move {
-                    let api = MonsterSearch;
-                    let result = search_for_creature(&api, &input).await;
-                    let _ = tx.send(result);
-                });
             }
-            KeyCode::Char(c) => {
-                if c == keymap.move_down && self.creature_search_selected.is_some() {
-                    if let Some(selected) = self.creature_search_selected {
-                        if selected + 1 < self.creature_search_result.len() {
-                            self.creature_search_selected = Some(selected + 1);
-                        }
-                    }
-                } else if c == keymap.move_up && self.creature_search_selected.is_some() {
-                    if let Some(selected) = self.creature_search_selected {
-                        if selected > 0 {
-                            self.creature_search_selected = Some(selected - 1);
-                        }
-                    }
-                } else if self.creature_search_selected.is_none() {
-                    self.creature_search_input.push(c);
+            KeyCode::Char(c) if c == keymap.increase_health => {
+                if let Some(limited_use) = limited_uses.get_mut(self.limited_uses_selected) {
+                    limited_use.restore();
                 }
             }
             _ => {}
         }
     }
 
-    fn new_encounter(&mut self) {
-        self.creature_list = CreatureList::default();
-    }
-
-    fn select_none(&mut self) {
-        self.creature_list.state.select(None);
-    }
-
-    fn select_next(&mut self) {
-        if self.save_creature_viewing.is_some() {
-            self.creature_list
-                .state
-                .select(self.save_creature_viewing.take());
-            self.save_creature_viewing = None;
+    fn handle_effects_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        let Some(i) = self.creature_list.state.selected() else {
+            self.show_effects_popup = false;
             return;
+        };
+        let in_list = self.effect_focus == EFFECT_LIST_FOCUS;
+        let effects = &mut self.creature_list.items[i].effects;
+        match key.code {
+            KeyCode::Esc => {
+                self.show_effects_popup = false;
+                self.effect_inputs = Default::default();
+            }
+            KeyCode::Tab => {
+                self.effect_focus = (self.effect_focus + 1) % (EFFECT_LIST_FOCUS + 1);
+            }
+            KeyCode::BackTab => {
+                self.effect_focus =
+                    (self.effect_focus + EFFECT_LIST_FOCUS) % (EFFECT_LIST_FOCUS + 1);
+            }
+            KeyCode::Enter => match self.ongoing_effect() {
+                Ok(effect) => {
+                    self.effect_message = Some(format!("Added {effect}"));
+                    self.creature_list.items[i].effects.push(effect);
+                    self.effect_inputs = Default::default();
+                    self.effect_focus = 0;
+                }
+                Err(e) => self.effect_message = Some(e),
+            },
+            KeyCode::Char(c)
+                if in_list && c == keymap.move_down && self.effect_selected + 1 < effects.len() =>
+            {
+                self.effect_selected += 1;
+            }
+            KeyCode::Char(c) if in_list && c == keymap.move_up => {
+                self.effect_selected = self.effect_selected.saturating_sub(1);
+            }
+            KeyCode::Char(' ') if in_list => {
+                if let Some(effect) = effects.get_mut(self.effect_selected) {
+                    effect.suppressed = !effect.suppressed;
+                }
+            }
+            KeyCode::Char(c) if in_list && c == keymap.delete_creature => {
+                if self.effect_selected < effects.len() {
+                    let effect = effects.remove(self.effect_selected);
+                    self.effect_selected =
+                        self.effect_selected.min(effects.len().saturating_sub(1));
+                    self.effect_message = Some(format!("Removed {}", effect.name));
+                }
+            }
+            _ => {
+                if let Some(input) = self.effect_inputs.get_mut(self.effect_focus) {
+                    input.handle_event(&Event::Key(*key));
+                }
+            }
         }
-        self.health_change = 0;
-        self.increasing_or_decreasing_health = false;
-
-        self.move_down_selected_creature();
     }
 
-    fn peek_on_next(&mut self) {
-        if self.save_creature_viewing.is_none() {
-            self.save_creature_viewing = self.creature_list.state.selected();
+    /// Read the effect from the inputs, it needs a name and something to do.
+    fn ongoing_effect(&self) -> Result<OngoingEffect, String> {
+        let [name, trigger, hit_points, save, suppressed_by] = &self.effect_inputs;
+        let name = name.value().trim();
+        if name.is_empty() {
+            return Err("The effect needs a name".to_string());
         }
-        self.move_down_selected_creature();
+        let mut effect = OngoingEffect::new(name, trigger.value().parse()?);
+        if !hit_points.value().trim().is_empty() {
+            effect.hit_points = Some(hit_points.value().parse()?);
+        }
+        if !save.value().trim().is_empty() {
+            effect.save = Some(parse_save(save.value())?);
+        }
+        if effect.hit_points.is_none() && effect.save.is_none() {
+            return Err("The effect needs an HP change or a save".to_string());
+        }
+        effect.suppressed_by = suppressed_by
+            .value()
+            .split(',')
+            .map(|damage_type| damage_type.trim().to_lowercase())
+            .filter(|damage_type| !damage_type.is_empty())
+            .collect();
+        Ok(effect)
     }
 
-    fn move_down_selected_creature(&mut self) {
-        let creature_count: usize = self.creature_list.items.len();
-        match self.creature_list.state.selected() {
-            Some(i) => {
-                if i + 1 < creature_count {
-                    self.creature_list.state.select_next();
-                } else {
-                    self.creature_list.state.select_first();
+    fn handle_zones_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        let in_list = self.zone_focus == ZONE_LIST_FOCUS;
+        let zones = &mut self.creature_list.zones;
+        match key.code {
+            KeyCode::Esc => {
+                self.show_zones_popup = false;
+                self.zone_inputs = Default::default();
+            }
+            KeyCode::Tab => {
+                self.zone_focus = (self.zone_focus + 1) % (ZONE_LIST_FOCUS + 1);
+            }
+            KeyCode::BackTab => {
+                self.zone_focus = (self.zone_focus + ZONE_LIST_FOCUS) % (ZONE_LIST_FOCUS + 1);
+            }
+            KeyCode::Enter => match self.zone_effect() {
+                Ok(zone) => {
+                    let round = self.creature_list.round;
+                    if let Some(owner) = self.creature_list.position(zone.owner) {
+                        let kind = EventKind::ZoneCreated {
+                            creature: zone.owner_name.clone(),
+                            zone: zone.name.clone(),
+                        };
+                        let owner = &self.creature_list.items[owner];
+                        self.combat_log.log_creature(round, owner, kind);
+                    }
+                    self.zone_message = Some(format!("Added {zone}"));
+                    self.creature_list.zones.push(zone);
+                    self.zone_inputs = Default::default();
+                    self.zone_focus = 0;
                 }
+                Err(e) => self.zone_message = Some(e),
+            },
+            KeyCode::Char(c)
+                if in_list && c == keymap.move_down && self.zone_selected + 1 < zones.len() =>
+            {
+                self.zone_selected += 1;
             }
-            None => {
-                if creature_count > 0 {
-                    self.creature_list.state.select_first();
+            KeyCode::Char(c) if in_list && c == keymap.move_up => {
+                self.zone_selected = self.zone_selected.saturating_sub(1);
+            }
+            KeyCode::Char(c) if in_list && c == keymap.delete_creature => {
+                if self.zone_selected < zones.len() {
+                    let zone = zones.remove(self.zone_selected);
+                    self.zone_selected = self.zone_selected.min(zones.len().saturating_sub(1));
+                    self.zone_message = Some(format!("Removed {}", zone.name));
+                }
+            }
+            _ => {
+                if let Some(input) = self.zone_inputs.get_mut(self.zone_focus) {
+                    input.handle_event(&Event::Key(*key));
+                }
+            }
+        }
+    }
+
+    /// Read the zone from the inputs, owned by the selected creature and
+    /// starting this round.
+    fn zone_effect(&self) -> Result<ZoneEffect, String> {
+        let Some(i) = self.creature_list.state.selected() else {
+            return Err("Select the creature that creates the zone".to_string());
+        };
+        let [name, rounds, reminder] = &self.zone_inputs;
+        let name = name.value().trim();
+        if name.is_empty() {
+            return Err("The zone needs a name".to_string());
+        }
+        let rounds: u32 = rounds
+            .value()
+            .trim()
+            .parse()
+            .ok()
+            .filter(|rounds| *rounds > 0)
+            .ok_or_else(|| "Rounds must be a positive number".to_string())?;
+        let round = self.creature_list.round.max(1);
+        let mut zone = ZoneEffect::new(name, &self.creature_list.items[i], round, rounds);
+        let reminder = reminder.value().trim();
+        if !reminder.is_empty() {
+            zone.reminder = Some(reminder.to_string());
+        }
+        Ok(zone)
+    }
+
+    fn open_spellcasting(&mut self) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        let creature = &self.creature_list.items[i];
+        if !creature.is_spellcaster() {
+            return;
+        }
+        self.spell_slot_selected = 0;
+        self.spell_selected = None;
+        self.spell_list_error = None;
+        self.show_spellcasting_popup = true;
+
+        let spell_list = creature.spell_list.clone().unwrap_or_default();
+        if creature.spells.is_empty() && !spell_list.is_empty() {
+            self.spell_list_loading = true;
+            let (tx, rx) = mpsc::unbounded_channel();
+            self.spell_list_result_rx = Some(rx);
+            let id = creature.id;
+            tokio::spawn(async move {
+                let api = SpellSearch;
+                let result = fetch_spell_list(&api, &spell_list).await;
+                let _ = tx.send((id, result));
+            });
+        }
+    }
+
+    fn handle_spellcasting_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        let Some(i) = self.creature_list.state.selected() else {
+            self.show_spellcasting_popup = false;
+            return;
+        };
+        let creature = &mut self.creature_list.items[i];
+        match key.code {
+            KeyCode::Esc => {
+                self.show_spellcasting_popup = false;
+                self.spell_list_loading = false;
+                self.spell_list_result_rx = None;
+            }
+            KeyCode::Char(c) if c == keymap.quit_app => {
+                self.show_spellcasting_popup = false;
+                self.spell_list_loading = false;
+                self.spell_list_result_rx = None;
+            }
+            KeyCode::Tab => {
+                if self.spell_selected.is_some() || creature.spells.is_empty() {
+                    self.spell_selected = None;
                 } else {
-                    self.creature_list.state.select(None);
+                    self.spell_selected = Some(0);
                 }
             }
+            KeyCode::Char(c) if c == keymap.move_down => match self.spell_selected {
+                Some(selected) => {
+                    if selected + 1 < creature.spells.len() {
+                        self.spell_selected = Some(selected + 1);
+                    }
+                }
+                None => {
+                    if self.spell_slot_selected + 1 < creature.spell_slots.len() {
+                        self.spell_slot_selected += 1;
+                    }
+                }
+            },
+            KeyCode::Char(c) if c == keymap.move_up => match self.spell_selected {
+                Some(selected) => self.spell_selected = Some(selected.saturating_sub(1)),
+                None => self.spell_slot_selected = self.spell_slot_selected.saturating_sub(1),
+            },
+            KeyCode::Char(c) if c == keymap.lower_health && self.spell_selected.is_none() => {
+                if let Some(spell_slot) = creature.spell_slots.get_mut(self.spell_slot_selected) {
+                    spell_slot.spend();
+                }
+            }
+            KeyCode::Char(c) if c == keymap.increase_health && self.spell_selected.is_none() => {
+                if let Some(spell_slot) = creature.spell_slots.get_mut(self.spell_slot_selected) {
+                    spell_slot.restore();
+                }
+            }
+            _ => {}
         }
     }
 
-    fn select_previous(&mut self) {
-        self.health_change = 0;
-        self.increasing_or_decreasing_health = false;
+    fn open_mob_attack(&mut self) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        if self.creature_list.items[i].attacks().is_empty() {
+            self.status_message = Some(format!(
+                "{} has no attacks with an attack bonus",
+                self.creature_list.items[i].name
+            ));
+            return;
+        }
+        self.mob_action_selected = 0;
+        self.mob_result = None;
+        self.show_mob_popup = true;
+    }
 
-        self.move_up_selected_creature();
+    fn handle_mob_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        let Some(i) = self.creature_list.state.selected() else {
+            self.show_mob_popup = false;
+            return;
+        };
+        let attack_count = self.creature_list.items[i].attacks().len();
+        match key.code {
+            KeyCode::Esc => {
+                self.show_mob_popup = false;
+                self.mob_ac_input = Input::default();
+            }
+            KeyCode::Tab => {
+                self.mob_mode = match self.mob_mode {
+                    MobMode::Table => MobMode::RollAll,
+                    MobMode::RollAll => MobMode::Table,
+                };
+            }
+            KeyCode::Char(c) if c == keymap.move_down => {
+                if self.mob_action_selected + 1 < attack_count {
+                    self.mob_action_selected += 1;
+                }
+            }
+            KeyCode::Char(c) if c == keymap.move_up => {
+                self.mob_action_selected = self.mob_action_selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                let Ok(target_ac) = self.mob_ac_input.value().trim().parse::<i64>() else {
+                    self.mob_result = Some("Target AC must be a number".to_string());
+                    return;
+                };
+                let attackers = self.creature_list.mob_members(i).len() as u32;
+                let creature = &self.creature_list.items[i];
+                let Some(action) = creature.attacks().get(self.mob_action_selected).copied() else {
+                    return;
+                };
+                let damage_dice = action
+                    .damage_dice
+                    .as_ref()
+                    .and_then(|dice| dice.parse::<DiceExpr>().ok());
+                let mob_attack = resolve_mob_attack(
+                    attackers,
+                    action.attack_bonus.unwrap_or(0),
+                    target_ac,
+                    damage_dice.as_ref(),
+                    self.mob_mode,
+                );
+                self.mob_result = Some(format!("{}: {mob_attack}", action.name));
+                self.combat_log.log_creature(
+                    self.creature_list.round,
+                    creature,
+                    EventKind::Roll {
+                        creature: format!("{} x{attackers}", creature.name),
+                        result: format!("{}: {mob_attack}", action.name),
+                    },
+                );
+            }
+            _ => {
+                self.mob_ac_input.handle_event(&Event::Key(*key));
+            }
+        }
     }
 
-    fn peek_on_previous(&mut self) {
-        if self.save_creature_viewing.is_none() {
-            self.save_creature_viewing = self.creature_list.state.selected();
+    fn handle_roll_check_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        let Some(i) = self.creature_list.state.selected() else {
+            self.show_roll_check_popup = false;
+            return;
+        };
+        let checks = Check::all();
+        match key.code {
+            KeyCode::Esc => self.show_roll_check_popup = false,
+            KeyCode::Tab => self.roll_check_mode = self.roll_check_mode.next(),
+            KeyCode::Char(c)
+                if c == keymap.move_down && self.roll_check_selected + 1 < checks.len() =>
+            {
+                self.roll_check_selected += 1;
+            }
+            KeyCode::Char(c) if c == keymap.move_up => {
+                self.roll_check_selected = self.roll_check_selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                let creature = &self.creature_list.items[i];
+                let check_roll = roll_check(
+                    creature,
+                    checks[self.roll_check_selected],
+                    self.roll_check_mode,
+                );
+                self.roll_check_result = Some(format!("{}: {check_roll}", creature.name));
+                self.combat_log.log_creature(
+                    self.creature_list.round,
+                    creature,
+                    EventKind::Roll {
+                        creature: creature.name.clone(),
+                        result: check_roll.to_string(),
+                    },
+                );
+            }
+            _ => {}
         }
-        self.move_up_selected_creature();
     }
 
-    fn move_up_selected_creature(&mut self) {
-        let creature_count: usize = self.creature_list.items.len();
+    /// Fill in the party from the players of the current list.
+    fn open_builder(&mut self) {
+        let levels: Vec<u32> = self
+            .creature_list
+            .items
+            .iter()
+            .filter(|creature| creature.faction == Faction::Player)
+            .filter_map(|creature| creature.level)
+            .collect();
+        let [party_size, party_level, ..] = &mut self.builder_inputs;
+        if party_size.value().is_empty() && !levels.is_empty() {
+            *party_size = Input::new(levels.len().to_string());
+            let average = levels.iter().sum::<u32>() / levels.len() as u32;
+            *party_level = Input::new(average.to_string());
+        }
+        self.builder_difficulty.get_or_insert(Difficulty::Medium);
+        self.builder_focus = 0;
+        self.show_builder_popup = true;
+    }
 
-        match self.creature_list.state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.creature_list.state.select_last();
-                } else {
-                    self.creature_list.state.select_previous();
+    fn handle_builder_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        // The six inputs, the difficulty and the suggestions
+        const FIELDS: usize = 8;
+        match key.code {
+            KeyCode::Esc => self.show_builder_popup = false,
+            KeyCode::Tab => self.builder_focus = (self.builder_focus + 1) % FIELDS,
+            KeyCode::BackTab => self.builder_focus = (self.builder_focus + FIELDS - 1) % FIELDS,
+            KeyCode::Char(' ') if self.builder_focus == 6 => {
+                self.builder_difficulty = self.builder_difficulty.map(Difficulty::next);
+                self.suggest_encounters();
+            }
+            KeyCode::Char(c)
+                if c == keymap.move_down
+                    && self.builder_focus == 7
+                    && self.builder_selected + 1 < self.builder_plans.len() =>
+            {
+                self.builder_selected += 1;
+            }
+            KeyCode::Char(c) if c == keymap.move_up && self.builder_focus == 7 => {
+                self.builder_selected = self.builder_selected.saturating_sub(1);
+            }
+            KeyCode::Enter if self.builder_focus == 7 => self.save_encounter_plan(),
+            KeyCode::Enter => match self.builder_filter() {
+                Ok(filter) => {
+                    self.builder_loading = true;
+                    self.builder_message = None;
+                    let (tx, rx) = mpsc::unbounded_channel();
+                    self.builder_result_rx = Some(rx);
+                    tokio::spawn(async move {
+                        let api = MonsterFilterSearch;
+                        let result = search_for_creatures_by_cr(&api, &filter).await;
+                        let _ = tx.send(result);
+                    });
+                }
+                Err(e) => self.builder_message = Some(e),
+            },
+            _ => {
+                if let Some(input) = self.builder_inputs.get_mut(self.builder_focus) {
+                    input.handle_event(&Event::Key(*key));
                 }
             }
-            None => {
-                if creature_count > 0 {
-                    self.creature_list.state.select_last();
-                } else {
-                    self.creature_list.state.select(None);
+        }
+    }
+
+    fn builder_party_levels(&self) -> Result<Vec<u32>, String> {
+        let [party_size, party_level, ..] = &self.builder_inputs;
+        let party_size: usize = party_size
+            .value()
+            .trim()
+            .parse()
+            .map_err(|_| "Party size must be a number".to_string())?;
+        let party_level: u32 = party_level
+            .value()
+            .trim()
+            .parse()
+            .ok()
+            .filter(|level| (1..=20).contains(level))
+            .ok_or("Party level must be between 1 and 20".to_string())?;
+        Ok(vec![party_level; party_size])
+    }
+
+    fn builder_filter(&self) -> Result<MonsterFilter, String> {
+        self.builder_party_levels()?;
+        let [_, _, cr_min, cr_max, creature_type, _] = &self.builder_inputs;
+        let parse_cr = |input: &Input, default: f64| match input.value().trim() {
+            "" => Ok(default),
+            cr => parse_challenge_rating(cr).ok_or(format!("Unknown challenge rating: {cr}")),
+        };
+        let creature_type = creature_type.value().trim();
+        Ok(MonsterFilter {
+            cr_min: parse_cr(cr_min, 0.0)?,
+            cr_max: parse_cr(cr_max, 30.0)?,
+            creature_type: if creature_type.is_empty() {
+                None
+            } else {
+                Some(creature_type.to_string())
+            },
+        })
+    }
+
+    fn suggest_encounters(&mut self) {
+        let (Ok(party_levels), Some(difficulty)) =
+            (self.builder_party_levels(), self.builder_difficulty)
+        else {
+            return;
+        };
+        self.builder_plans =
+            suggest_encounters(&self.builder_candidates, &party_levels, difficulty, 20);
+        self.builder_selected = 0;
+        if self.builder_plans.is_empty() && !self.builder_candidates.is_empty() {
+            self.builder_message = Some(format!(
+                "No {difficulty} mix among {} monsters",
+                self.builder_candidates.len()
+            ));
+        }
+    }
+
+    fn save_encounter_plan(&mut self) {
+        let Some(plan) = self.builder_plans.get(self.builder_selected) else {
+            return;
+        };
+        let name = self.builder_inputs[5].value().trim();
+        if name.is_empty() {
+            self.builder_message = Some("Name the encounter before saving it".to_string());
+            return;
+        }
+        let Some(dir) = get_encounters_dir() else {
+            self.builder_message = Some("Unable to find the config directory".to_string());
+            return;
+        };
+        self.builder_message = Some(match plan.to_template(name).save(&dir) {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Unable to save the encounter: {e}"),
+        });
+    }
+
+    fn handle_log_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        match key.code {
+            KeyCode::Esc => self.show_log_popup = false,
+            KeyCode::Char(c) if c == keymap.quit_app => self.show_log_popup = false,
+            KeyCode::Char(c) if c == keymap.move_down => self.log_scroll += 1,
+            KeyCode::Char(c) if c == keymap.move_up => {
+                self.log_scroll = self.log_scroll.saturating_sub(1);
+            }
+            KeyCode::Tab => {
+                self.log_export_format = match self.log_export_format {
+                    ExportFormat::Markdown => ExportFormat::Json,
+                    ExportFormat::Json => ExportFormat::Markdown,
+                };
+            }
+            KeyCode::Enter => {
+                let Some(dir) = get_logs_dir() else {
+                    self.status_message = Some("Unable to find the config directory".to_string());
+                    return;
+                };
+                self.status_message =
+                    Some(match self.combat_log.export(&dir, self.log_export_format) {
+                        Ok(path) => format!("Combat log exported to {}", path.display()),
+                        Err(e) => format!("Unable to export the combat log: {e}"),
+                    });
+            }
+            _ => {}
+        }
+    }
+
+    fn open_templates(&mut self) {
+        self.templates = get_encounters_dir()
+            .map(|dir| load_templates(&dir))
+            .unwrap_or_default();
+        self.template_selected = if self.templates.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.template_message = None;
+        self.show_template_popup = true;
+    }
+
+    fn handle_template_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        match key.code {
+            KeyCode::Esc => {
+                self.show_template_popup = false;
+                self.template_name_input = Input::default();
+            }
+            KeyCode::Tab => {
+                self.template_selected = match self.template_selected {
+                    None if !self.templates.is_empty() => Some(0),
+                    _ => None,
+                };
+            }
+            KeyCode::Char(c) if c == keymap.move_down && self.template_selected.is_some() => {
+                self.template_selected = self
+                    .template_selected
+                    .map(|i| (i + 1).min(self.templates.len() - 1));
+            }
+            KeyCode::Char(c) if c == keymap.move_up && self.template_selected.is_some() => {
+                self.template_selected = self.template_selected.map(|i| i.saturating_sub(1));
+            }
+            KeyCode::Enter => match self.template_selected {
+                Some(i) => self.load_template(self.templates[i].clone()),
+                None => self.save_template(),
+            },
+            _ if self.template_selected.is_none() => {
+                self.template_name_input.handle_event(&Event::Key(*key));
+            }
+            _ => {}
+        }
+    }
+
+    /// Fetch the monsters of the template and replace the NPCs with them once
+    /// they arrive.
+    pub fn load_template(&mut self, template: EncounterTemplate) {
+        self.template_message = Some(format!("Loading {}...", template.name));
+        self.status_message = self.template_message.clone();
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.template_result_rx = Some(rx);
+        self.template_loading = Some(template.clone());
+        tokio::spawn(async move {
+            let api = MonsterSearch;
+            let result = fetch_encounter_monsters(&api, &template).await;
+            let _ = tx.send(result);
+        });
+    }
+
+    fn save_template(&mut self) {
+        let name = self.template_name_input.value().trim();
+        if name.is_empty() {
+            self.template_message = Some("Name the encounter before saving it".to_string());
+            return;
+        }
+        let Some(dir) = get_encounters_dir() else {
+            self.template_message = Some("Unable to find the config directory".to_string());
+            return;
+        };
+        let template = EncounterTemplate::from_creature_list(name, &self.creature_list);
+        match template.save(&dir) {
+            Ok(path) => {
+                self.template_message = Some(format!("Saved to {}", path.display()));
+                self.template_name_input = Input::default();
+                self.templates = load_templates(&dir);
+            }
+            Err(e) => self.template_message = Some(format!("Unable to save the encounter: {e}")),
+        }
+    }
+
+    /// Replace the NPCs with the monsters of the template, keeping the party.
+    fn load_encounter(&mut self, template: &EncounterTemplate, monsters: EncounterMonsters) {
+        let mut commands = deletions(&self.creature_list, |creature| {
+            creature.faction != Faction::Player
+        });
+        let mut npcs = Vec::new();
+        for (monster, count) in &monsters {
+            for _ in 0..*count {
+                let mut creature = CreatureItem::new_npc(monster);
+                if template.hp_mode == HpMode::Rolled {
+                    creature.roll_hit_points();
                 }
+                creature.id = self.creature_list.next_id();
+                commands.push(Command::Insert {
+                    creature: creature.clone(),
+                });
+                npcs.push(creature);
             }
         }
+        self.creature_list.replace_npcs(npcs);
+        self.history.record(Command::Batch {
+            name: format!("Load {}", template.name),
+            commands,
+        });
+        self.show_template_popup = false;
+        self.status_message = Some(match &template.notes {
+            Some(notes) => format!("Loaded {}: {notes}", template.name),
+            None => format!("Loaded {}", template.name),
+        });
     }
 
-    fn delete_creature(&mut self) {
-        if let Some(i) = self.creature_list.state.selected() {
-            self.creature_list.items.remove(i);
+    fn handle_area_effect_input(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.show_area_effect_popup = false;
+                self.area_effect_inputs = Default::default();
+            }
+            KeyCode::Tab => {
+                self.area_effect_focus = (self.area_effect_focus + 1) % 5;
+            }
+            KeyCode::BackTab => {
+                self.area_effect_focus = (self.area_effect_focus + 4) % 5;
+            }
+            KeyCode::Char(' ') if self.area_effect_focus == 4 => {
+                self.area_effect_no_half = !self.area_effect_no_half;
+            }
+            KeyCode::Enter if self.area_effect_targets.is_empty() => {
+                self.area_effect_result =
+                    vec!["Already resolved, mark the next targets and open it again".to_string()];
+            }
+            KeyCode::Enter => match self.area_effect() {
+                Ok(effect) => {
+                    let damage = effect.damage;
+                    let mut results = vec![format!(
+                        "{} save DC {} for {} damage",
+                        effect.ability, effect.dc, damage
+                    )];
+                    let round = self.creature_list.round;
+                    let source = format!("{} save DC {}", effect.ability, effect.dc);
+                    let targets: Vec<usize> = std::mem::take(&mut self.area_effect_targets)
+                        .into_iter()
+                        .filter_map(|id| self.creature_list.position(id))
+                        .collect();
+                    let mut commands = Vec::new();
+                    for i in targets {
+                        let creature = &mut self.creature_list.items[i];
+                        let result = resolve_save(creature, &effect, roll_d20());
+                        let before = creature.hit_points;
+                        creature.take_damage(result.damage);
+                        if let Some(damage_type) = &effect.damage_type {
+                            if result.damage > 0 {
+                                suppress_effects(creature, damage_type);
+                            }
+                        }
+                        if creature.untracked_hp {
+                            results.push(format!("{result}, HP untracked"));
+                        } else {
+                            results.push(result.to_string());
+                        }
+
+                        self.combat_log.log_creature(
+                            round,
+                            creature,
+                            EventKind::Roll {
+                                creature: creature.name.clone(),
+                                result: format!(
+                                    "{} {} ({})",
+                                    source,
+                                    result.roll + result.bonus,
+                                    if result.success { "success" } else { "failure" }
+                                ),
+                            },
+                        );
+                        commands.extend(self.log_hit_points(i, before, Some(source.clone())));
+                    }
+                    // The whole effect is undone at once
+                    if !commands.is_empty() {
+                        self.history.record(Command::Batch {
+                            name: results[0].clone(),
+                            commands,
+                        });
+                    }
+                    self.creature_list.unmark_all();
+                    self.area_effect_result = results;
+                }
+                Err(e) => self.area_effect_result = vec![e],
+            },
+            _ => {
+                if let Some(input) = self.area_effect_inputs.get_mut(self.area_effect_focus) {
+                    input.handle_event(&Event::Key(*key));
+                }
+            }
         }
     }
 
-    fn lower_health(&mut self) {
-        if let Some(i) = self.creature_list.state.selected() {
-            if self.creature_list.items[i].hit_points > 0 {
-                self.increasing_or_decreasing_health = true;
-                self.creature_list.items[i].hit_points -= 1;
-                self.health_change -= 1;
+    /// Read the area effect from the inputs, the damage is rolled here so
+    /// every creature takes the same roll.
+    fn area_effect(&self) -> Result<AreaEffect, String> {
+        let [ability, dc, damage, damage_type] = &self.area_effect_inputs;
+        let ability: Ability = ability.value().parse()?;
+        let dc: i64 = dc
+            .value()
+            .trim()
+            .parse()
+            .map_err(|_| "DC must be a number".to_string())?;
+        let damage: DiceExpr = damage.value().parse().map_err(|e| format!("{e}"))?;
+        let damage_type = damage_type.value().trim();
+        Ok(AreaEffect {
+            ability,
+            dc,
+            damage: damage.roll().total.max(0) as u64,
+            damage_type: if damage_type.is_empty() {
+                None
+            } else {
+                Some(damage_type.to_string())
+            },
+            half_on_success: !self.area_effect_no_half,
+        })
+    }
+
+    fn handle_ready_input(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                if let Some(i) = self.creature_list.state.selected() {
+                    let trigger = self.ready_input.value().trim().to_string();
+                    self.creature_list.items[i].readied = if trigger.is_empty() {
+                        None
+                    } else {
+                        Some(trigger)
+                    };
+                }
+                self.show_ready_popup = false;
+                self.ready_input = Input::default();
             }
-            if self.creature_list.items[i].hit_points == 0 {
-                self.creature_list.items[i].status = Status::Dead;
+            KeyCode::Esc => {
+                self.show_ready_popup = false;
+                self.ready_input = Input::default();
+            }
+            _ => {
+                self.ready_input.handle_event(&Event::Key(*key));
             }
         }
     }
 
-    fn increase_health(&mut self) {
-        if let Some(i) = self.creature_list.state.selected() {
-            if self.creature_list.items[i].hit_points < self.creature_list.items[i].max_hit_points {
-                self.increasing_or_decreasing_health = true;
-                self.creature_list.items[i].hit_points += 1;
-                self.health_change += 1;
+    /// Delay the turn of the selected creature, or choose where a delayed
+    /// creature comes back in.
+    fn delay_turn(&mut self) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        if self.creature_list.items[i].delayed {
+            self.open_resume(i);
+            return;
+        }
+
+        self.creature_list.items[i].delayed = true;
+        if self.save_creature_viewing.is_none() {
+            self.select_next();
+        }
+    }
+
+    /// A delayed creature can come back after the creature whose turn it is
+    /// or anyone after it in the round.
+    fn open_resume(&mut self, delayed: usize) {
+        let current = self.save_creature_viewing.unwrap_or(delayed);
+        let start = if current == delayed { 0 } else { current };
+        self.resume_candidates = self
+            .creature_list
+            .items
+            .iter()
+            .skip(start)
+            .filter(|creature| !creature.delayed)
+            .map(|creature| creature.id)
+            .collect();
+        if self.resume_candidates.is_empty() {
+            self.status_message = Some("There is no creature to act after".to_string());
+            return;
+        }
+        self.resume_id = self.creature_list.items[delayed].id;
+        self.resume_selected = 0;
+        self.show_resume_popup = true;
+    }
+
+    fn handle_resume_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        match key.code {
+            KeyCode::Esc => self.show_resume_popup = false,
+            KeyCode::Char(c) if c == keymap.quit_app => self.show_resume_popup = false,
+            KeyCode::Char(c)
+                if c == keymap.move_down
+                    && self.resume_selected + 1 < self.resume_candidates.len() =>
+            {
+                self.resume_selected += 1;
             }
-            if self.creature_list.items[i].hit_points > 0 {
-                self.creature_list.items[i].status = Status::Alive;
+            KeyCode::Char(c) if c == keymap.move_up => {
+                self.resume_selected = self.resume_selected.saturating_sub(1);
             }
+            KeyCode::Enter => {
+                self.show_resume_popup = false;
+                self.resume_delayed();
+            }
+            _ => {}
+        }
+    }
+
+    /// Put the delayed creature back after the chosen creature. Coming back
+    /// after the creature whose turn it is starts its turn right away,
+    /// otherwise the turn stays where it was.
+    fn resume_delayed(&mut self) {
+        let list = &self.creature_list;
+        let after = self
+            .resume_candidates
+            .get(self.resume_selected)
+            .and_then(|id| list.position(*id));
+        let (Some(delayed), Some(after)) = (list.position(self.resume_id), after) else {
+            return;
+        };
+        let current = self
+            .save_creature_viewing
+            .take()
+            .or(list.state.selected())
+            .map(|i| list.items[i].id);
+        let acts_now = current == Some(list.items[after].id);
+
+        let resumed = self.creature_list.resume_delayed(delayed, after);
+        if acts_now {
+            self.creature_list.state.select(Some(resumed));
+            self.creature_list.start_turn(resumed);
+        } else {
+            let current = current.and_then(|id| self.creature_list.position(id));
+            self.creature_list.state.select(current);
         }
     }
 
-    fn insert_new(&mut self) {
-        let creature = CreatureItem::new_npc(&ApiCreatureSearchItem::default());
-        self.creature_list.add_new_creature(creature);
+    async fn handle_creature_search_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        match key.code {
+            KeyCode::Tab => {
+                if !self.creature_search_result.is_empty() {
+                    if self.creature_search_selected.is_some() {
+                        self.creature_search_selected = None;
+                    } else {
+                        self.creature_search_selected = Some(0);
+                    }
+                } else {
+                    self.creature_search_selected = None;
+                }
+            }
+            KeyCode::Esc => {
+                self.show_creature_search_popup = false;
+            }
+            KeyCode::Backspace => {
+                self.creature_search_input.pop();
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.creature_search_selected {
+                    if let Some(selected_creature) = self.creature_search_result.get(selected) {
+                        self.add_creature(CreatureItem::new_npc(selected_creature));
+                        self.show_creature_search_popup = false;
+                        return;
+                    }
+                }
+
+                self.creature_search_loading = true;
+                let (tx, rx) = mpsc::unbounded_channel();
+                self.creature_search_result_rx = Some(rx);
+                let input = self.creature_search_input.clone();
+                tokio::spawn(async move {
+                    let api = MonsterSearch;
+                    let result = search_for_creature(&api, &input).await;
+                    let _ = tx.send(result);
+                });
+            }
+            KeyCode::Char(c) => {
+                if c == keymap.move_down && self.creature_search_selected.is_some() {
+                    if let Some(selected) = self.creature_search_selected {
+                        if selected + 1 < self.creature_search_result.len() {
+                            self.creature_search_selected = Some(selected + 1);
+                        }
+                    }
+                } else if c == keymap.move_up && self.creature_search_selected.is_some() {
+                    if let Some(selected) = self.creature_search_selected {
+                        if selected > 0 {
+                            self.creature_search_selected = Some(selected - 1);
+                        }
+                    }
+                } else if self.creature_search_selected.is_none() {
+                    self.creature_search_input.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_spell_search_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        match key.code {
+            KeyCode::Tab => {
+                if !self.spell_search_result.is_empty() {
+                    if self.spell_search_selected.is_some() {
+                        self.spell_search_selected = None;
+                    } else {
+                        self.spell_search_selected = Some(0);
+                    }
+                } else {
+                    self.spell_search_selected = None;
+                }
+            }
+            KeyCode::Esc => {
+                self.show_spell_search_popup = false;
+            }
+            KeyCode::Backspace => {
+                self.spell_search_input.pop();
+            }
+            KeyCode::Enter => {
+                if self.spell_search_selected.is_some() {
+                    return;
+                }
+
+                self.spell_search_loading = true;
+                let (tx, rx) = mpsc::unbounded_channel();
+                self.spell_search_result_rx = Some(rx);
+                let input = self.spell_search_input.clone();
+                tokio::spawn(async move {
+                    let api = SpellSearch;
+                    let result = search_for_spell(&api, &input).await;
+                    let _ = tx.send(result);
+                });
+            }
+            KeyCode::Char(c) => {
+                if c == keymap.move_down && self.spell_search_selected.is_some() {
+                    if let Some(selected) = self.spell_search_selected {
+                        if selected + 1 < self.spell_search_result.len() {
+                            self.spell_search_selected = Some(selected + 1);
+                        }
+                    }
+                } else if c == keymap.move_up && self.spell_search_selected.is_some() {
+                    if let Some(selected) = self.spell_search_selected {
+                        if selected > 0 {
+                            self.spell_search_selected = Some(selected - 1);
+                        }
+                    }
+                } else if self.spell_search_selected.is_none() {
+                    self.spell_search_input.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_reference(&mut self) {
+        self.reference_input.clear();
+        self.reference_result = offline_conditions("");
+        self.reference_selected = None;
+        self.show_reference_popup = true;
+    }
+
+    fn open_creature_conditions(&mut self) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        self.reference_input.clear();
+        self.reference_result = self.creature_list.items[i]
+            .conditions
+            .iter()
+            .map(|name| find_condition(name).unwrap_or_else(|| ApiReference::condition(name, "")))
+            .collect();
+        self.reference_selected = if self.reference_result.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.show_reference_popup = true;
+    }
+
+    fn handle_reference_input(&mut self, key: &KeyEvent) {
+        let keymap: KeyBindings = KeyBindings::default();
+        match key.code {
+            KeyCode::Tab => {
+                if !self.reference_result.is_empty() {
+                    if self.reference_selected.is_some() {
+                        self.reference_selected = None;
+                    } else {
+                        self.reference_selected = Some(0);
+                    }
+                } else {
+                    self.reference_selected = None;
+                }
+            }
+            KeyCode::Esc => {
+                self.show_reference_popup = false;
+            }
+            KeyCode::Backspace => {
+                self.reference_input.pop();
+            }
+            KeyCode::Enter => {
+                if let Some(selected) = self.reference_selected {
+                    // Apply or remove the selected condition on the selected creature
+                    if let (Some(reference), Some(i)) = (
+                        self.reference_result.get(selected),
+                        self.creature_list.state.selected(),
+                    ) {
+                        if reference.kind == ReferenceKind::Condition {
+                            let creature = &mut self.creature_list.items[i];
+                            creature.toggle_condition(&reference.name);
+                            let (creature, condition) =
+                                (creature.name.clone(), reference.name.clone());
+                            let kind = if self.creature_list.items[i].has_condition(&condition) {
+                                EventKind::ConditionApplied {
+                                    creature,
+                                    condition,
+                                }
+                            } else {
+                                EventKind::ConditionRemoved {
+                                    creature,
+                                    condition,
+                                }
+                            };
+                            self.combat_log.log_creature(
+                                self.creature_list.round,
+                                &self.creature_list.items[i],
+                                kind,
+                            );
+                        }
+                    }
+                    return;
+                }
+
+                self.reference_loading = true;
+                let (tx, rx) = mpsc::unbounded_channel();
+                self.reference_result_rx = Some(rx);
+                let input = self.reference_input.clone();
+                tokio::spawn(async move {
+                    let api = ReferenceSearch;
+                    let result = search_reference(&api, &input).await;
+                    let _ = tx.send(result);
+                });
+            }
+            KeyCode::Char(c) => {
+                if c == keymap.move_down && self.reference_selected.is_some() {
+                    if let Some(selected) = self.reference_selected {
+                        if selected + 1 < self.reference_result.len() {
+                            self.reference_selected = Some(selected + 1);
+                        }
+                    }
+                } else if c == keymap.move_up && self.reference_selected.is_some() {
+                    if let Some(selected) = self.reference_selected {
+                        if selected > 0 {
+                            self.reference_selected = Some(selected - 1);
+                        }
+                    }
+                } else if self.reference_selected.is_none() {
+                    self.reference_input.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Start over with the party of `default.yml`, which can be undone. The
+    /// combat log is exported first and kept when that fails.
+    fn new_encounter(&mut self) {
+        if self.combat_log.public_events().next().is_some() {
+            let exported = get_logs_dir()
+                .ok_or_else(|| "unable to find the config directory".to_string())
+                .and_then(|dir| {
+                    self.combat_log
+                        .export(&dir, self.log_export_format)
+                        .map_err(|e| e.to_string())
+                });
+            match exported {
+                Ok(path) => {
+                    self.status_message =
+                        Some(format!("Combat log exported to {}", path.display()));
+                }
+                Err(e) => {
+                    self.status_message = Some(format!(
+                        "Unable to export the combat log ({e}), export the log first"
+                    ));
+                    return;
+                }
+            }
+        }
+        let mut fresh = CreatureList::default();
+        fresh.renumber_after(&self.creature_list);
+        let mut commands = deletions(&self.creature_list, |_| true);
+        commands.extend(fresh.items.iter().map(|creature| Command::Insert {
+            creature: creature.clone(),
+        }));
+        self.creature_list = fresh;
+        self.history.record(Command::Batch {
+            name: "New encounter".to_string(),
+            commands,
+        });
+        self.combat_log = CombatLog::default();
+    }
+
+    fn select_none(&mut self) {
+        self.creature_list.state.select(None);
+    }
+
+    fn select_next(&mut self) {
+        if self.save_creature_viewing.is_some() {
+            self.creature_list
+                .state
+                .select(self.save_creature_viewing.take());
+            self.save_creature_viewing = None;
+            return;
+        }
+        self.health_change = 0;
+        self.increasing_or_decreasing_health = false;
+
+        if self.creature_list.in_combat() {
+            if let Some(i) = self.creature_list.state.selected() {
+                for j in self.creature_list.turn_members(i) {
+                    self.fire_effects(j, Trigger::TurnEnd);
+                }
+            }
+        }
+
+        // Delayed and surprised creatures are not part of the current pass
+        let mut previous = self.creature_list.state.selected();
+        for _ in 0..self.creature_list.items.len() {
+            self.move_down_selected_creature();
+            let current = self.creature_list.state.selected();
+            if let (Some(p), Some(c)) = (previous, current) {
+                if c <= p {
+                    self.creature_list.next_round();
+                }
+            }
+            previous = current;
+            match current {
+                Some(i) if self.creature_list.skips_turn(i) => continue,
+                _ => break,
+            }
+        }
+        if let Some(i) = self.creature_list.state.selected() {
+            self.creature_list.start_turn(i);
+            if self.creature_list.in_combat() {
+                let creature = &self.creature_list.items[i];
+                let kind = EventKind::TurnStart {
+                    creature: creature.name.clone(),
+                };
+                self.combat_log
+                    .log_creature(self.creature_list.round, creature, kind);
+                self.update_zones(i);
+                for j in self.creature_list.turn_members(i) {
+                    self.fire_effects(j, Trigger::TurnStart);
+                }
+            }
+        }
+    }
+
+    /// Fire the ongoing effects of the creature, logging what they did and
+    /// reporting it in the footer.
+    fn fire_effects(&mut self, i: usize, trigger: Trigger) {
+        let before = self.creature_list.items[i].hit_points;
+        let outcomes = trigger_effects(&mut self.creature_list.items[i], trigger);
+        if outcomes.is_empty() {
+            return;
+        }
+
+        let round = self.creature_list.round;
+        let creature = &self.creature_list.items[i];
+        for outcome in &outcomes {
+            if let Some(save) = &outcome.save {
+                let kind = EventKind::Roll {
+                    creature: creature.name.clone(),
+                    result: format!(
+                        "{}: {save}{}",
+                        outcome.effect,
+                        if outcome.ended() { ", ends" } else { "" }
+                    ),
+                };
+                self.combat_log.log_creature(round, creature, kind);
+            }
+        }
+        let source = outcomes
+            .iter()
+            .filter(|outcome| outcome.change != 0)
+            .map(|outcome| outcome.effect.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let report = outcomes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.report(report);
+        self.record_hit_points(i, before, Some(source));
+    }
+
+    /// End the zones that are over at the start of the creature's turn and
+    /// remind of the ones that are left.
+    fn update_zones(&mut self, i: usize) {
+        let round = self.creature_list.round;
+        let current = self.creature_list.items[i].id;
+        for zone in expire_zones(&mut self.creature_list.zones, round, current) {
+            let kind = EventKind::ZoneEnded {
+                creature: zone.owner_name.clone(),
+                zone: zone.name.clone(),
+            };
+            match self.creature_list.position(zone.owner) {
+                Some(owner) => {
+                    let owner = &self.creature_list.items[owner];
+                    self.combat_log.log_creature(round, owner, kind);
+                }
+                None => self.combat_log.log(round, kind),
+            }
+            self.report(format!("{} ends", zone.name));
+        }
+        for reminder in zone_reminders(&self.creature_list.zones) {
+            self.report(reminder);
+        }
+    }
+
+    /// Show the message in the footer after what this key press has shown.
+    fn report(&mut self, message: String) {
+        self.status_message = Some(match self.status_message.take() {
+            Some(shown) => format!("{shown}; {message}"),
+            None => message,
+        });
+    }
+
+    fn start_combat(&mut self) {
+        if self.creature_list.in_combat() {
+            self.status_message = Some(format!(
+                "Combat is already running, round {}",
+                self.creature_list.round
+            ));
+            return;
+        }
+        match self.creature_list.start_combat() {
+            Ok(()) => {
+                self.save_creature_viewing = None;
+                self.creature_list.state.select(None);
+                self.select_next();
+            }
+            Err(missing) => {
+                self.status_message =
+                    Some(format!("Initiative missing for: {}", missing.join(", ")));
+            }
+        }
+    }
+
+    fn peek_on_next(&mut self) {
+        if self.save_creature_viewing.is_none() {
+            self.save_creature_viewing = self.creature_list.state.selected();
+        }
+        self.move_down_selected_creature();
+    }
+
+    fn move_down_selected_creature(&mut self) {
+        let creature_count: usize = self.creature_list.items.len();
+        match self.creature_list.state.selected() {
+            Some(i) => {
+                if i + 1 < creature_count {
+                    self.creature_list.state.select_next();
+                } else {
+                    self.creature_list.state.select_first();
+                }
+            }
+            None => {
+                if creature_count > 0 {
+                    self.creature_list.state.select_first();
+                } else {
+                    self.creature_list.state.select(None);
+                }
+            }
+        }
+    }
+
+    fn select_previous(&mut self) {
+        self.health_change = 0;
+        self.increasing_or_decreasing_health = false;
+
+        self.move_up_selected_creature();
+    }
+
+    fn peek_on_previous(&mut self) {
+        if self.save_creature_viewing.is_none() {
+            self.save_creature_viewing = self.creature_list.state.selected();
+        }
+        self.move_up_selected_creature();
+    }
+
+    fn move_up_selected_creature(&mut self) {
+        let creature_count: usize = self.creature_list.items.len();
+
+        match self.creature_list.state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.creature_list.state.select_last();
+                } else {
+                    self.creature_list.state.select_previous();
+                }
+            }
+            None => {
+                if creature_count > 0 {
+                    self.creature_list.state.select_last();
+                } else {
+                    self.creature_list.state.select(None);
+                }
+            }
+        }
+    }
+
+    /// Hide the selected creature from the players, or reveal it.
+    fn toggle_hidden(&mut self) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        let creature = &mut self.creature_list.items[i];
+        creature.hidden = !creature.hidden;
+        if creature.hidden {
+            self.status_message = Some(format!("{} is hidden", creature.name));
+        } else {
+            let kind = EventKind::Revealed {
+                creature: creature.name.clone(),
+            };
+            self.status_message = Some(kind.to_string());
+            self.combat_log
+                .log_creature(self.creature_list.round, creature, kind);
+        }
+    }
+
+    fn delete_creature(&mut self) {
+        if let Some(i) = self.creature_list.state.selected() {
+            let creature = self.creature_list.items.remove(i);
+            self.history.record(Command::Delete { index: i, creature });
+        }
+    }
+
+    fn lower_health(&mut self) {
+        if let Some(i) = self.creature_list.state.selected() {
+            if self.untracked_hp(i) {
+                return;
+            }
+            if self.creature_list.items[i].hit_points > 0 {
+                self.increasing_or_decreasing_health = true;
+                self.health_change -= 1;
+            }
+            self.damage(i, 1);
+        }
+    }
+
+    fn increase_health(&mut self) {
+        if let Some(i) = self.creature_list.state.selected() {
+            if self.untracked_hp(i) {
+                return;
+            }
+            if self.creature_list.items[i].hit_points < self.creature_list.items[i].max_hit_points {
+                self.increasing_or_decreasing_health = true;
+                self.health_change += 1;
+            }
+            self.heal(i, 1);
+        }
+    }
+
+    /// Tell the DM when the HP of the creature is left to its player.
+    fn untracked_hp(&mut self, i: usize) -> bool {
+        let creature = &self.creature_list.items[i];
+        if creature.untracked_hp {
+            self.status_message = Some(format!("The HP of {} is not tracked", creature.name));
+        }
+        creature.untracked_hp
+    }
+
+    fn damage(&mut self, i: usize, amount: u64) {
+        let before = self.creature_list.items[i].hit_points;
+        self.creature_list.items[i].take_damage(amount);
+        self.record_hit_points(i, before, None);
+    }
+
+    fn heal(&mut self, i: usize, amount: u64) {
+        let before = self.creature_list.items[i].hit_points;
+        self.creature_list.items[i].heal(amount);
+        self.record_hit_points(i, before, None);
+    }
+
+    fn insert_new(&mut self) {
+        let creature = CreatureItem::new_npc(&ApiCreatureSearchItem::default());
+        self.add_creature(creature);
+    }
+
+    fn add_creature(&mut self, mut creature: CreatureItem) {
+        creature.id = self.creature_list.next_id();
+        self.history.record(Command::Insert {
+            creature: creature.clone(),
+        });
+        self.creature_list.add_new_creature(creature);
+    }
+
+    fn record_hit_points(&mut self, i: usize, before: u64, source: Option<String>) {
+        if let Some(command) = self.log_hit_points(i, before, source) {
+            self.history.record(command);
+        }
+    }
+
+    /// Log the HP change of the creature, its death and whether it would
+    /// flee, and return the edit for the history.
+    fn log_hit_points(&mut self, i: usize, before: u64, source: Option<String>) -> Option<Command> {
+        let creature = &self.creature_list.items[i];
+        if creature.hit_points == before {
+            return None;
+        }
+        let round = self.creature_list.round;
+        let change = creature.hit_points as i64 - before as i64;
+        self.combat_log
+            .log_hit_points(round, creature, change, source);
+        if creature.hit_points == 0 {
+            let kind = EventKind::Death {
+                creature: creature.name.clone(),
+            };
+            self.combat_log.log_creature(round, creature, kind);
+        }
+        let flees = self.creature_list.health.breaks_morale(creature, before);
+        let name = creature.name.clone();
+        let command = Command::Edit {
+            id: creature.id,
+            name: creature.name.clone(),
+            edit: Edit::HitPoints {
+                before,
+                after: creature.hit_points,
+            },
+        };
+        if flees {
+            self.report(format!("{name} would flee"));
+        }
+        Some(command)
+    }
+
+    fn undo(&mut self) {
+        self.health_change = 0;
+        self.increasing_or_decreasing_health = false;
+        self.status_message = Some(match self.history.undo(&mut self.creature_list) {
+            Some(command) => format!("Undo: {command}"),
+            None => "Nothing to undo".to_string(),
+        });
+    }
+
+    fn redo(&mut self) {
+        self.health_change = 0;
+        self.increasing_or_decreasing_health = false;
+        self.status_message = Some(match self.history.redo(&mut self.creature_list) {
+            Some(command) => format!("Redo: {command}"),
+            None => "Nothing to redo".to_string(),
+        });
+    }
+}
+
+impl Widget for &mut App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [header_area, main_area, footer_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+
+        let [list_area, item_area] =
+            Layout::vertical([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(main_area);
+
+        App::render_header(header_area, buf);
+        self.render_footer(footer_area, buf);
+
+        // Zones are shown next to the list while there are any
+        let list_area = if self.creature_list.zones.is_empty() {
+            list_area
+        } else {
+            let [list_area, zones_area] =
+                Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)])
+                    .areas(list_area);
+            self.render_zones(zones_area, buf);
+            list_area
+        };
+        self.render_list(list_area, buf);
+        self.render_selected_item(item_area, buf);
+
+        if self.show_creature_search_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_creature_search_popup(self, area, buf);
+        }
+
+        if self.show_spell_search_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_spell_search_popup(self, area, buf);
+        }
+
+        if self.show_reference_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_reference_popup(self, area, buf);
+        }
+
+        if self.show_initiative_popup {
+            let area = App::popup_initiative_area(area);
+            App::clear_area(area, buf);
+            App::render_initiative_popup(self, area, buf);
+        }
+
+        if self.show_description_popup {
+            let area = App::popup_description_area(area);
+            App::clear_area(area, buf);
+            App::render_description_popup(self, area, buf);
+        }
+
+        if self.show_ready_popup {
+            let area = App::popup_ready_area(area);
+            App::clear_area(area, buf);
+            App::render_ready_popup(self, area, buf);
+        }
+
+        if self.show_area_effect_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_area_effect_popup(self, area, buf);
+        }
+
+        if self.show_log_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_log_popup(self, area, buf);
+        }
+
+        if self.show_history_popup {
+            let area = App::popup_limited_uses_area(area);
+            App::clear_area(area, buf);
+            App::render_history_popup(self, area, buf);
+        }
+
+        if self.show_template_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_template_popup(self, area, buf);
+        }
+
+        if self.show_builder_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_builder_popup(self, area, buf);
+        }
+
+        if self.show_difficulty_popup {
+            let area = App::popup_limited_uses_area(area);
+            App::clear_area(area, buf);
+            App::render_difficulty_popup(self, area, buf);
+        }
+
+        if self.show_senses_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_senses_popup(self, area, buf);
+        }
+
+        if self.show_roll_check_popup {
+            let area = App::popup_limited_uses_area(area);
+            App::clear_area(area, buf);
+            App::render_roll_check_popup(self, area, buf);
+        }
+
+        if self.show_mob_popup {
+            let area = App::popup_limited_uses_area(area);
+            App::clear_area(area, buf);
+            App::render_mob_popup(self, area, buf);
+        }
+
+        if self.show_limited_uses_popup {
+            let area = App::popup_limited_uses_area(area);
+            App::clear_area(area, buf);
+            App::render_limited_uses_popup(self, area, buf);
+        }
+
+        if self.show_resume_popup {
+            let area = App::popup_limited_uses_area(area);
+            App::clear_area(area, buf);
+            App::render_resume_popup(self, area, buf);
+        }
+
+        if self.show_spellcasting_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_spellcasting_popup(self, area, buf);
+        }
+
+        if self.show_effects_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_effects_popup(self, area, buf);
+        }
+
+        if self.show_zones_popup {
+            let area = App::popup_search_area(main_area);
+            App::clear_area(area, buf);
+            App::render_zones_popup(self, area, buf);
+        }
+    }
+}
+
+/// Rendering logic for the app
+impl App {
+    fn clear_area(area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+    }
+
+    fn render_initiative_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Set Initiative")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        let input = Paragraph::new(self.initiative_input.value())
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .alignment(ratatui::layout::Alignment::Center);
+
+        input.render(area, buf);
+    }
+
+    fn render_description_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Set Description")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        let input = Paragraph::new(self.description_input.value().to_string())
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .alignment(ratatui::layout::Alignment::Center);
+
+        input.render(area, buf);
+    }
+
+    fn render_ready_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Ready Action (Trigger)")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        let input = Paragraph::new(self.ready_input.value().to_string())
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .alignment(ratatui::layout::Alignment::Center);
+
+        input.render(area, buf);
+    }
+
+    fn render_area_effect_popup(&self, area: Rect, buf: &mut Buffer) {
+        let targets: Vec<String> = self
+            .creature_list
+            .targets()
+            .into_iter()
+            .map(|i| self.creature_list.items[i].name.clone())
+            .collect();
+        Block::bordered()
+            .title(format!("Area Effect: {}", targets.join(", ")))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [inputs_area, result_area] =
+            Layout::vertical([Constraint::Length(6), Constraint::Min(0)])
+                .margin(1)
+                .areas(area);
+        let [ability_area, dc_area, damage_area, damage_type_area, half_area] =
+            Layout::horizontal([Constraint::Fill(1); 5]).areas(inputs_area);
+
+        let titles = ["Ability", "DC", "Damage", "Damage Type"];
+        let areas = [ability_area, dc_area, damage_area, damage_type_area];
+        for (field, ((input, title), field_area)) in self
+            .area_effect_inputs
+            .iter()
+            .zip(titles)
+            .zip(areas)
+            .enumerate()
+        {
+            let mut block = Block::default().borders(Borders::ALL).title(title);
+            if field == self.area_effect_focus {
+                block = block.border_style(SELECTED_STYLE);
+            }
+            Paragraph::new(input.value())
+                .block(block)
+                .fg(TEXT_FG_COLOR)
+                .render(field_area, buf);
+        }
+        let mut block = Block::default().borders(Borders::ALL).title("Half on Save");
+        if self.area_effect_focus == 4 {
+            block = block.border_style(SELECTED_STYLE);
+        }
+        Paragraph::new(if self.area_effect_no_half {
+            "No"
+        } else {
+            "Yes"
+        })
+        .block(block)
+        .fg(TEXT_FG_COLOR)
+        .render(half_area, buf);
+
+        Paragraph::new(self.area_effect_result.join("\n"))
+            .block(Block::default().borders(Borders::ALL).title("Saves"))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(result_area, buf);
+    }
+
+    fn render_effects_popup(&self, area: Rect, buf: &mut Buffer) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        let creature = &self.creature_list.items[i];
+        Block::bordered()
+            .title(format!("Ongoing Effects: {}", creature.name))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [inputs_area, list_area, message_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .areas(area);
+        let input_areas: [Rect; 5] =
+            Layout::horizontal([Constraint::Fill(1); 5]).areas(inputs_area);
+
+        let titles = [
+            "Name",
+            "When (start/end)",
+            "HP (+10, 1d6 fire)",
+            "Save (CON 13)",
+            "Stopped By (fire, acid)",
+        ];
+        for (field, ((input, title), field_area)) in self
+            .effect_inputs
+            .iter()
+            .zip(titles)
+            .zip(input_areas)
+            .enumerate()
+        {
+            let mut block = Block::default().borders(Borders::ALL).title(title);
+            if field == self.effect_focus {
+                block = block.border_style(SELECTED_STYLE);
+            }
+            Paragraph::new(input.value())
+                .block(block)
+                .fg(TEXT_FG_COLOR)
+                .render(field_area, buf);
+        }
+
+        let mut block = Block::default().borders(Borders::ALL).title("Effects");
+        let mut state = ratatui::widgets::ListState::default();
+        if self.effect_focus == EFFECT_LIST_FOCUS {
+            block = block.border_style(SELECTED_STYLE);
+            state.select(Some(self.effect_selected));
+        }
+        let items: Vec<ListItem> = creature
+            .effects
+            .iter()
+            .map(|effect| ListItem::new(effect.to_string()))
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(list, list_area, buf, &mut state);
+
+        if let Some(message) = &self.effect_message {
+            Paragraph::new(message.as_str())
+                .fg(TEXT_FG_COLOR)
+                .render(message_area, buf);
+        }
+    }
+
+    fn render_zones(&self, area: Rect, buf: &mut Buffer) {
+        let round = self.creature_list.round;
+        let lines: Vec<String> = self
+            .creature_list
+            .zones
+            .iter()
+            .map(|zone| zone.summary(round))
+            .collect();
+        Paragraph::new(lines.join("\n"))
+            .block(Block::bordered().title("Zones").bg(NORMAL_ROW_BG))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    fn render_zones_popup(&self, area: Rect, buf: &mut Buffer) {
+        let owner = match self.creature_list.state.selected() {
+            Some(i) => self.creature_list.items[i].name.clone(),
+            None => "No creature selected".to_string(),
+        };
+        Block::bordered()
+            .title(format!("Zone Effects: {owner}"))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [inputs_area, list_area, message_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .margin(1)
+        .areas(area);
+        let input_areas: [Rect; 3] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Fill(2),
+        ])
+        .areas(inputs_area);
+
+        let titles = ["Name", "Rounds", "Reminder"];
+        for (field, ((input, title), field_area)) in self
+            .zone_inputs
+            .iter()
+            .zip(titles)
+            .zip(input_areas)
+            .enumerate()
+        {
+            let mut block = Block::default().borders(Borders::ALL).title(title);
+            if field == self.zone_focus {
+                block = block.border_style(SELECTED_STYLE);
+            }
+            Paragraph::new(input.value())
+                .block(block)
+                .fg(TEXT_FG_COLOR)
+                .render(field_area, buf);
+        }
+
+        let mut block = Block::default().borders(Borders::ALL).title("Zones");
+        let mut state = ratatui::widgets::ListState::default();
+        if self.zone_focus == ZONE_LIST_FOCUS {
+            block = block.border_style(SELECTED_STYLE);
+            state.select(Some(self.zone_selected));
+        }
+        let round = self.creature_list.round;
+        let items: Vec<ListItem> = self
+            .creature_list
+            .zones
+            .iter()
+            .map(|zone| match &zone.reminder {
+                Some(reminder) => ListItem::new(format!("{}: {reminder}", zone.summary(round))),
+                None => ListItem::new(zone.summary(round)),
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(list, list_area, buf, &mut state);
+
+        if let Some(message) = &self.zone_message {
+            Paragraph::new(message.as_str())
+                .fg(TEXT_FG_COLOR)
+                .render(message_area, buf);
+        }
+    }
+
+    fn render_log_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(format!(
+                "Combat Log (Enter to export as {}, Tab to change)",
+                self.log_export_format
+            ))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        let events = self.combat_log.events();
+        let text = if events.is_empty() {
+            "Nothing happened yet".to_string()
+        } else {
+            events
+                .iter()
+                .map(|event| event.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        Paragraph::new(text)
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .scroll((self.log_scroll, 0))
+            .render(area, buf);
+    }
+
+    fn render_history_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("History (U to undo, Y to redo)")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        // Undone commands above the applied ones, the next to redo closest
+        let mut items: Vec<ListItem> = self
+            .history
+            .undone()
+            .map(|command| ListItem::new(format!("  {command}")).fg(SLATE.c500))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        let current = items.len();
+        items.extend(
+            self.history
+                .done()
+                .map(|command| ListItem::new(format!("  {command}")).fg(TEXT_FG_COLOR)),
+        );
+        if items.is_empty() {
+            Paragraph::new("Nothing happened yet")
+                .block(block)
+                .fg(TEXT_FG_COLOR)
+                .centered()
+                .render(area, buf);
+            return;
+        }
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(if current < items.len() {
+            Some(current)
+        } else {
+            None
+        });
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">");
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+
+    fn render_template_popup(&self, area: Rect, buf: &mut Buffer) {
+        Block::bordered()
+            .title("Encounter Templates")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [input_area, list_area, message_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .margin(1)
+        .areas(area);
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title("Save current encounter as");
+        if self.template_selected.is_none() {
+            block = block.border_style(SELECTED_STYLE);
+        }
+        Paragraph::new(self.template_name_input.value())
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .render(input_area, buf);
+
+        let templates: Vec<ListItem> = self
+            .templates
+            .iter()
+            .map(|template| ListItem::new(template.to_string()))
+            .collect();
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(self.template_selected);
+        let list = List::new(templates)
+            .block(Block::default().borders(Borders::ALL).title("Load"))
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(list, list_area, buf, &mut state);
+
+        Paragraph::new(self.template_message.clone().unwrap_or_default())
+            .block(Block::default().borders(Borders::ALL))
+            .fg(TEXT_FG_COLOR)
+            .render(message_area, buf);
+    }
+
+    fn render_builder_popup(&self, area: Rect, buf: &mut Buffer) {
+        let budget = match (self.builder_party_levels(), self.builder_difficulty) {
+            (Ok(party_levels), Some(difficulty)) => {
+                format!(" - {} XP budget", xp_budget(&party_levels, difficulty))
+            }
+            _ => String::new(),
+        };
+        Block::bordered()
+            .title(format!("Encounter Builder{budget}"))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [party_area, filter_area, plans_area, message_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .margin(1)
+        .areas(area);
+        let [size_area, level_area, difficulty_area, name_area] =
+            Layout::horizontal([Constraint::Fill(1); 4]).areas(party_area);
+        let [cr_min_area, cr_max_area, type_area] =
+            Layout::horizontal([Constraint::Fill(1); 3]).areas(filter_area);
+
+        let titles = [
+            "Party Size",
+            "Party Level",
+            "Min CR",
+            "Max CR",
+            "Type",
+            "Encounter Name",
+        ];
+        let areas = [
+            size_area,
+            level_area,
+            cr_min_area,
+            cr_max_area,
+            type_area,
+            name_area,
+        ];
+        for (field, ((input, title), field_area)) in self
+            .builder_inputs
+            .iter()
+            .zip(titles)
+            .zip(areas)
+            .enumerate()
+        {
+            let mut block = Block::default().borders(Borders::ALL).title(title);
+            if field == self.builder_focus {
+                block = block.border_style(SELECTED_STYLE);
+            }
+            Paragraph::new(input.value())
+                .block(block)
+                .fg(TEXT_FG_COLOR)
+                .render(field_area, buf);
+        }
+        let mut block = Block::default().borders(Borders::ALL).title("Difficulty");
+        if self.builder_focus == 6 {
+            block = block.border_style(SELECTED_STYLE);
+        }
+        Paragraph::new(
+            self.builder_difficulty
+                .map(|difficulty| difficulty.to_string())
+                .unwrap_or_default(),
+        )
+        .block(block)
+        .fg(TEXT_FG_COLOR)
+        .render(difficulty_area, buf);
+
+        let plans: Vec<ListItem> = self
+            .builder_plans
+            .iter()
+            .map(|plan| ListItem::new(plan.to_string()))
+            .collect();
+        let mut block = Block::default().borders(Borders::ALL).title("Suggestions");
+        if self.builder_focus == 7 {
+            block = block.border_style(SELECTED_STYLE);
+        }
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(Some(self.builder_selected));
+        let list = List::new(plans)
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(list, plans_area, buf, &mut state);
+
+        let message = if self.builder_loading {
+            "Searching...".to_string()
+        } else {
+            self.builder_message.clone().unwrap_or_default()
+        };
+        Paragraph::new(message)
+            .block(Block::default().borders(Borders::ALL))
+            .fg(TEXT_FG_COLOR)
+            .render(message_area, buf);
+    }
+
+    fn render_difficulty_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Encounter Difficulty")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        let summary = encounter_summary(&self.creature_list);
+        let text = if summary.party_levels.is_empty() {
+            "No player has a level, add them to default.yml".to_string()
+        } else {
+            summary.to_string()
+        };
+        Paragraph::new(text)
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    fn render_senses_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(format!(
+                "Senses (sorted by {}, Tab to change)",
+                self.senses_sort
+            ))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
+
+        let range = |range: Option<u32>| range.map_or("-".to_string(), |r| format!("{r} ft."));
+        let rows: Vec<Row> = senses_overview(&self.creature_list, self.senses_sort)
+            .into_iter()
+            .map(|row| {
+                Row::new(vec![
+                    Cell::from(row.name),
+                    Cell::from(row.passive_perception.to_string()),
+                    Cell::from(range(row.senses.darkvision)),
+                    Cell::from(range(row.senses.blindsight)),
+                    Cell::from(range(row.senses.tremorsense)),
+                    Cell::from(range(row.senses.truesight)),
+                    Cell::from(row.languages),
+                ])
+            })
+            .collect();
+        let header = Row::new(SensesColumn::ALL.map(|column| {
+            let cell = Cell::from(column.to_string());
+            if column == self.senses_sort {
+                cell.style(SELECTED_STYLE)
+            } else {
+                cell
+            }
+        }))
+        .style(WTII_HEADER_STYLE);
+
+        Widget::render(
+            Table::new(
+                rows,
+                [
+                    Constraint::Percentage(20),
+                    Constraint::Length(8),
+                    Constraint::Length(11),
+                    Constraint::Length(11),
+                    Constraint::Length(12),
+                    Constraint::Length(10),
+                    Constraint::Min(0),
+                ],
+            )
+            .header(header)
+            .block(block)
+            .fg(TEXT_FG_COLOR),
+            area,
+            buf,
+        );
+    }
+
+    fn render_roll_check_popup(&self, area: Rect, buf: &mut Buffer) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        let creature = &self.creature_list.items[i];
+
+        Block::bordered()
+            .title(format!(
+                "Roll Check: {} ({}, Tab to change)",
+                creature.name, self.roll_check_mode
+            ))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [checks_area, result_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(4)])
+                .margin(1)
+                .areas(area);
+
+        let checks: Vec<ListItem> = Check::all()
+            .into_iter()
+            .map(|check| ListItem::new(format!("{check} {:+}", check.bonus(creature))))
+            .collect();
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(Some(self.roll_check_selected));
+        let list = List::new(checks)
+            .block(Block::default().borders(Borders::ALL).title("Check"))
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(list, checks_area, buf, &mut state);
+
+        Paragraph::new(self.roll_check_result.clone().unwrap_or_default())
+            .block(Block::default().borders(Borders::ALL).title("Result"))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(result_area, buf);
     }
-}
 
-impl Widget for &mut App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let [header_area, main_area, footer_area] = Layout::vertical([
-            Constraint::Length(2),
-            Constraint::Fill(1),
-            Constraint::Length(1),
+    fn render_mob_popup(&self, area: Rect, buf: &mut Buffer) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        let creature = &self.creature_list.items[i];
+        let attackers = self.creature_list.mob_members(i).len();
+
+        Block::bordered()
+            .title(format!("Mob Attack: {} x{}", creature.name, attackers))
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        let [input_area, actions_area, result_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(6),
         ])
+        .margin(1)
         .areas(area);
 
-        let [list_area, item_area] =
-            Layout::vertical([Constraint::Percentage(30), Constraint::Percentage(70)])
-                .areas(main_area);
+        Paragraph::new(self.mob_ac_input.value())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Target AC ({}, Tab to change)", self.mob_mode)),
+            )
+            .fg(TEXT_FG_COLOR)
+            .render(input_area, buf);
 
-        App::render_header(header_area, buf);
-        App::render_footer(footer_area, buf);
+        let actions: Vec<ListItem> = creature
+            .attacks()
+            .iter()
+            .map(|action| {
+                let attack_bonus = action.attack_bonus.unwrap_or(0);
+                match &action.damage_dice {
+                    Some(dice) => {
+                        ListItem::new(format!("{} (+{attack_bonus}, {dice})", action.name))
+                    }
+                    None => ListItem::new(format!("{} (+{attack_bonus})", action.name)),
+                }
+            })
+            .collect();
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(Some(self.mob_action_selected));
+        let list = List::new(actions)
+            .block(Block::default().borders(Borders::ALL).title("Attack"))
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(list, actions_area, buf, &mut state);
 
-        self.render_list(list_area, buf);
-        self.render_selected_item(item_area, buf);
+        Paragraph::new(self.mob_result.clone().unwrap_or_default())
+            .block(Block::default().borders(Borders::ALL).title("Result"))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(result_area, buf);
+    }
 
-        if self.show_creature_search_popup {
-            let area = App::popup_search_area(main_area);
-            App::clear_area(area, buf);
-            App::render_creature_search_popup(self, area, buf);
-        }
+    fn render_limited_uses_popup(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title("Limited Uses")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG);
 
-        if self.show_initiative_popup {
-            let area = App::popup_initiative_area(area);
-            App::clear_area(area, buf);
-            App::render_initiative_popup(self, area, buf);
+        let limited_uses = match self.creature_list.state.selected() {
+            Some(i) => self.creature_list.items[i].limited_uses(),
+            None => Vec::new(),
+        };
+        if limited_uses.is_empty() {
+            Paragraph::new("No limited uses")
+                .block(block)
+                .fg(TEXT_FG_COLOR)
+                .centered()
+                .render(area, buf);
+            return;
         }
 
-        if self.show_description_popup {
-            let area = App::popup_description_area(area);
-            App::clear_area(area, buf);
-            App::render_description_popup(self, area, buf);
-        }
-    }
-}
+        let items: Vec<ListItem> = limited_uses
+            .iter()
+            .map(|limited_use| ListItem::new(limited_use.to_string()))
+            .collect();
 
-/// Rendering logic for the app
-impl App {
-    fn clear_area(area: Rect, buf: &mut Buffer) {
-        Clear.render(area, buf);
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(Some(self.limited_uses_selected));
+
+        let list = List::new(items)
+            .block(block)
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+
+        StatefulWidget::render(list, area, buf, &mut state);
     }
 
-    fn render_initiative_popup(&self, area: Rect, buf: &mut Buffer) {
+    fn render_resume_popup(&self, area: Rect, buf: &mut Buffer) {
+        let list = &self.creature_list;
+        let name = list
+            .position(self.resume_id)
+            .map(|i| list.items[i].name.clone())
+            .unwrap_or_default();
         let block = Block::bordered()
-            .title("Set Initiative")
+            .title(format!("{name} acts after"))
             .borders(Borders::ALL)
             .bg(NORMAL_ROW_BG);
 
-        let input = Paragraph::new(self.initiative_input.value())
+        let items: Vec<ListItem> = self
+            .resume_candidates
+            .iter()
+            .filter_map(|id| list.position(*id))
+            .map(|i| {
+                let creature = &list.items[i];
+                match creature.initiative {
+                    Some(initiative) => ListItem::new(format!("{} ({initiative})", creature.name)),
+                    None => ListItem::new(creature.name.clone()),
+                }
+            })
+            .collect();
+
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(Some(self.resume_selected));
+
+        let resume_list = List::new(items)
             .block(block)
             .fg(TEXT_FG_COLOR)
-            .wrap(Wrap { trim: false })
-            .alignment(ratatui::layout::Alignment::Center);
+            .highlight_style(SELECTED_STYLE);
 
-        input.render(area, buf);
+        StatefulWidget::render(resume_list, area, buf, &mut state);
     }
 
-    fn render_description_popup(&self, area: Rect, buf: &mut Buffer) {
-        let block = Block::bordered()
-            .title("Set Description")
+    fn render_spellcasting_popup(&self, area: Rect, buf: &mut Buffer) {
+        let Some(i) = self.creature_list.state.selected() else {
+            return;
+        };
+        let creature = &self.creature_list.items[i];
+
+        Block::bordered()
+            .title(format!("Spellcasting: {}", creature.name))
             .borders(Borders::ALL)
-            .bg(NORMAL_ROW_BG);
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
 
-        let input = Paragraph::new(self.description_input.value().to_string())
-            .block(block)
+        let [list_area, spell_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .margin(1)
+                .areas(area);
+        let [slots_area, spells_area] = Layout::vertical([
+            Constraint::Length(creature.spell_slots.len() as u16 + 2),
+            Constraint::Min(0),
+        ])
+        .areas(list_area);
+
+        // Spell slots, spent with h and restored with l
+        let slots: Vec<ListItem> = creature
+            .spell_slots
+            .iter()
+            .map(|slot| ListItem::new(slot.to_string()))
+            .collect();
+        let mut slots_state = ratatui::widgets::ListState::default();
+        if self.spell_selected.is_none() && !slots.is_empty() {
+            slots_state.select(Some(self.spell_slot_selected));
+        }
+        let slots_list = List::new(slots)
+            .block(Block::default().borders(Borders::ALL).title("Spell Slots"))
             .fg(TEXT_FG_COLOR)
-            .wrap(Wrap { trim: false })
-            .alignment(ratatui::layout::Alignment::Center);
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(slots_list, slots_area, buf, &mut slots_state);
 
-        input.render(area, buf);
+        // Spells resolved from the creature's spell list
+        let spells: Vec<ListItem> = creature
+            .spells
+            .iter()
+            .map(|spell| ListItem::new(spell.name.clone()))
+            .collect();
+        let mut spells_state = ratatui::widgets::ListState::default();
+        spells_state.select(self.spell_selected);
+        let spells_title = if self.spell_list_loading {
+            "Spells (Loading...)".to_string()
+        } else if let Some(e) = &self.spell_list_error {
+            format!("Spells ({e})")
+        } else {
+            "Spells".to_string()
+        };
+        let spells_list = List::new(spells)
+            .block(Block::default().borders(Borders::ALL).title(spells_title))
+            .fg(TEXT_FG_COLOR)
+            .highlight_style(SELECTED_STYLE);
+        StatefulWidget::render(spells_list, spells_area, buf, &mut spells_state);
+
+        let spell_info = self
+            .spell_selected
+            .and_then(|selected| creature.spells.get(selected))
+            .map(|spell| spell.to_string())
+            .unwrap_or_default();
+        Paragraph::new(spell_info)
+            .block(Block::default().borders(Borders::ALL).title("Spell"))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(spell_area, buf);
     }
 
     fn render_creature_search_popup(&self, area: Rect, buf: &mut Buffer) {
@@ -528,6 +3228,143 @@ impl App {
         StatefulWidget::render(list, chunks[1], buf, &mut state);
     }
 
+    fn render_spell_search_popup(&self, area: Rect, buf: &mut Buffer) {
+        // Draw the popup background and border
+        Block::bordered()
+            .title("Spell Search")
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        // Split the popup into input, results and spell info areas
+        let [search_area, info_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .margin(1)
+                .areas(area);
+        let [input_area, results_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(search_area);
+
+        // Render the search input
+        let input = Paragraph::new(Text::from(self.spell_search_input.as_str()))
+            .block(Block::default().borders(Borders::ALL).title("Search"));
+        input.render(input_area, buf);
+
+        // Prepare the results as a list of items
+        let results: Vec<ListItem> = self
+            .spell_search_result
+            .iter()
+            .map(|item| ListItem::new(item.name.clone()))
+            .collect();
+
+        // Set up the selection state
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(self.spell_search_selected);
+
+        // Render the results list with highlight for the selected row
+        let list = List::new(results)
+            .block(Block::default().borders(Borders::ALL).title("Results"))
+            .highlight_style(SELECTED_STYLE);
+
+        // Render the loading state if applicable
+        if self.spell_search_loading {
+            let loading = Paragraph::new("Loading...").block(Block::default());
+            loading.render(input_area, buf);
+        }
+
+        StatefulWidget::render(list, results_area, buf, &mut state);
+
+        // Render the selected spell
+        let spell_info = self
+            .spell_search_selected
+            .and_then(|selected| self.spell_search_result.get(selected))
+            .map(|spell| spell.to_string())
+            .unwrap_or_default();
+        Paragraph::new(spell_info)
+            .block(Block::default().borders(Borders::ALL).title("Spell"))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(info_area, buf);
+    }
+
+    fn render_reference_popup(&self, area: Rect, buf: &mut Buffer) {
+        let creature = self
+            .creature_list
+            .state
+            .selected()
+            .map(|i| &self.creature_list.items[i]);
+
+        // Draw the popup background and border
+        let title = match creature {
+            Some(creature) => format!(
+                "Rules Reference (Enter toggles condition on {})",
+                creature.name
+            ),
+            None => "Rules Reference".to_string(),
+        };
+        Block::bordered()
+            .title(title)
+            .borders(Borders::ALL)
+            .bg(NORMAL_ROW_BG)
+            .render(area, buf);
+
+        // Split the popup into input, results and reference text areas
+        let [search_area, info_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .margin(1)
+                .areas(area);
+        let [input_area, results_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(search_area);
+
+        // Render the search input
+        let input = Paragraph::new(Text::from(self.reference_input.as_str()))
+            .block(Block::default().borders(Borders::ALL).title("Search"));
+        input.render(input_area, buf);
+
+        // Prepare the results, marking conditions applied to the selected creature
+        let results: Vec<ListItem> = self
+            .reference_result
+            .iter()
+            .map(|item| {
+                let applied = item.kind == ReferenceKind::Condition
+                    && creature.is_some_and(|c| c.has_condition(&item.name));
+                if applied {
+                    ListItem::new(format!("✓ {} ({})", item.name, item.kind))
+                } else {
+                    ListItem::new(format!("  {} ({})", item.name, item.kind))
+                }
+            })
+            .collect();
+
+        // Set up the selection state
+        let mut state = ratatui::widgets::ListState::default();
+        state.select(self.reference_selected);
+
+        // Render the results list with highlight for the selected row
+        let list = List::new(results)
+            .block(Block::default().borders(Borders::ALL).title("Results"))
+            .highlight_style(SELECTED_STYLE);
+
+        // Render the loading state if applicable
+        if self.reference_loading {
+            let loading = Paragraph::new("Loading...").block(Block::default());
+            loading.render(input_area, buf);
+        }
+
+        StatefulWidget::render(list, results_area, buf, &mut state);
+
+        // Render the selected reference
+        let reference_info = self
+            .reference_selected
+            .and_then(|selected| self.reference_result.get(selected))
+            .map(|reference| reference.to_string())
+            .unwrap_or_default();
+        Paragraph::new(reference_info)
+            .block(Block::default().borders(Borders::ALL).title("Reference"))
+            .fg(TEXT_FG_COLOR)
+            .wrap(Wrap { trim: false })
+            .render(info_area, buf);
+    }
+
     fn popup_search_area(area: Rect) -> Rect {
         let vertical = Layout::vertical([Constraint::Percentage(90)]).flex(Flex::Center);
         let horizontal = Layout::horizontal([Constraint::Percentage(90)]).flex(Flex::Center);
@@ -552,6 +3389,22 @@ impl App {
         area
     }
 
+    fn popup_ready_area(area: Rect) -> Rect {
+        let vertical = Layout::vertical([Constraint::Percentage(30)]).flex(Flex::Center);
+        let horizontal = Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+        area
+    }
+
+    fn popup_limited_uses_area(area: Rect) -> Rect {
+        let vertical = Layout::vertical([Constraint::Percentage(50)]).flex(Flex::Center);
+        let horizontal = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center);
+        let [area] = vertical.areas(area);
+        let [area] = horizontal.areas(area);
+        area
+    }
+
     fn render_header(area: Rect, buf: &mut Buffer) {
         Paragraph::new("Who's Turn Is It?")
             .bold()
@@ -559,7 +3412,15 @@ impl App {
             .render(area, buf);
     }
 
-    fn render_footer(area: Rect, buf: &mut Buffer) {
+    fn render_footer(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(message) = &self.status_message {
+            Paragraph::new(message.as_str())
+                .bold()
+                .centered()
+                .render(area, buf);
+            return;
+        }
+
         let keymap: KeyBindings = KeyBindings::default();
         Paragraph::new(format!(
             "Use {} for new encounter, \
@@ -578,23 +3439,46 @@ impl App {
     }
 
     fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
+        let title = if self.creature_list.in_combat() {
+            format!("Order - Round {}", self.creature_list.round)
+        } else {
+            "Order".to_string()
+        };
         let block = Block::new()
-            .title(Line::raw("Order").centered())
+            .title(Line::raw(title).centered())
             .borders(Borders::TOP)
             .border_set(symbols::border::EMPTY)
             .border_style(WTII_HEADER_STYLE)
             .bg(NORMAL_ROW_BG);
 
-        let items: Vec<ListItem> = self
-            .creature_list
-            .items
-            .iter()
-            .enumerate()
-            .map(|(i, creature)| {
-                let color = alternate_colors(i);
-                ListItem::from(creature).bg(color)
-            })
-            .collect();
+        // Collapsed groups are shown as one row, so rows and creatures can differ
+        let creature_list = &self.creature_list;
+        let mut items: Vec<ListItem> = Vec::new();
+        let mut selected_row = None;
+        for (i, creature) in creature_list.items.iter().enumerate() {
+            if creature_list.is_folded(i) {
+                if creature_list.state.selected() == Some(i) {
+                    selected_row = items.len().checked_sub(1);
+                }
+                continue;
+            }
+            if creature_list.state.selected() == Some(i) {
+                selected_row = Some(items.len());
+            }
+            let color = alternate_colors(items.len());
+            let item = match creature_list.group_of(i) {
+                Some(name) if creature_list.collapsed_groups.contains(name) => {
+                    let members: Vec<&CreatureItem> = creature_list
+                        .group_members(i)
+                        .into_iter()
+                        .map(|j| &creature_list.items[j])
+                        .collect();
+                    group_list_item(&members)
+                }
+                _ => creature_list_item(creature, &creature_list.health),
+            };
+            items.push(item.bg(color));
+        }
 
         let list = List::new(items)
             .block(block)
@@ -602,7 +3486,13 @@ impl App {
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::Always);
 
-        StatefulWidget::render(list, area, buf, &mut self.creature_list.state);
+        if selected_row == self.creature_list.state.selected() {
+            StatefulWidget::render(list, area, buf, &mut self.creature_list.state);
+        } else {
+            let mut state = ratatui::widgets::ListState::default();
+            state.select(selected_row);
+            StatefulWidget::render(list, area, buf, &mut state);
+        }
     }
 
     fn render_selected_item(&self, area: Rect, buf: &mut Buffer) {
@@ -610,29 +3500,36 @@ impl App {
             let initiative_is_set: bool = self.creature_list.items[i].initiative.is_some();
             match self.creature_list.items[i].faction {
                 Faction::Npc => {
-                    let lines = npc_info(self, i);
-                    lines
-                        .into_iter()
-                        .map(|(k, v, f)| {
-                            if f == TextFormatting::Line {
-                                format!("{k}: {v}")
-                            } else {
-                                format!("\n==={k}===\n{v}")
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                }
-                Faction::Player => format!(
-                    " Initiative: {}\n Name: {}\n HP: {}",
-                    if initiative_is_set {
-                        self.creature_list.items[i].initiative.unwrap().to_string()
-                    } else {
-                        "Not set yet".to_string()
-                    },
-                    self.creature_list.items[i].name,
-                    self.creature_list.items[i].hit_points,
-                ),
+                    let health_change = (self.increasing_or_decreasing_health
+                        && self.save_creature_viewing.is_none())
+                    .then_some(self.health_change);
+                    to_text(&npc_info(&self.creature_list.items[i], health_change))
+                }
+                Faction::Player => {
+                    let creature = &self.creature_list.items[i];
+                    let mut info = format!(
+                        " Initiative: {}\n Name: {}\n HP: {}",
+                        if initiative_is_set {
+                            creature.initiative.unwrap().to_string()
+                        } else {
+                            "Not set yet".to_string()
+                        },
+                        creature.name,
+                        if creature.untracked_hp {
+                            "Not tracked".to_string()
+                        } else {
+                            format!("{}/{}", creature.hit_points, creature.max_hit_points)
+                        },
+                    );
+                    if let Some(level) = self.creature_list.items[i].level {
+                        info.push_str(&format!("\n Level: {level}"));
+                    }
+                    let conditions = &self.creature_list.items[i].conditions;
+                    if !conditions.is_empty() {
+                        info.push_str(&format!("\n Conditions: {}", conditions.join(", ")));
+                    }
+                    info
+                }
             }
         } else {
             "Nothing selected...".to_string()
@@ -655,223 +3552,8 @@ impl App {
     }
 }
 
-fn npc_info(app: &App, i: usize) -> Vec<(String, String, TextFormatting)> {
-    let c = &app.creature_list.items[i];
-    let mut lines = Vec::new();
-
-    let initiative = if let Some(val) = c.initiative {
-        val.to_string()
-    } else {
-        "Not set yet".to_string()
-    };
-    lines.push(("Initiative".to_string(), initiative, TextFormatting::Line));
-    lines.push(("Name".to_string(), c.name.clone(), TextFormatting::Line));
-
-    let mut hp_str = c.hit_points.to_string();
-    if app.increasing_or_decreasing_health && app.save_creature_viewing.is_none() {
-        hp_str.push_str(&format!(" ({})", app.health_change));
-    }
-    lines.push(("HP".to_string(), hp_str, TextFormatting::Line));
-
-    if let Some(ac) = c.armor_class {
-        lines.push(("AC".to_string(), ac.to_string(), TextFormatting::Line));
-    }
-    if let Some(val) = c.strength {
-        lines.push((
-            "Strength".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.strength_save {
-        lines.push((
-            "Strength Save".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.dexterity {
-        lines.push((
-            "Dexterity".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.dexterity_save {
-        lines.push((
-            "Dexterity Save".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.constitution {
-        lines.push((
-            "Constitution".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.constitution_save {
-        lines.push((
-            "Constitution Save".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.intelligence {
-        lines.push((
-            "Intelligence".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.intelligence_save {
-        lines.push((
-            "Intelligence Save".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.wisdom {
-        lines.push(("Wisdom".to_string(), val.to_string(), TextFormatting::Line));
-    }
-    if let Some(val) = c.wisdom_save {
-        lines.push((
-            "Wisdom Save".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.charisma {
-        lines.push((
-            "Charisma".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(val) = c.charisma_save {
-        lines.push((
-            "Charisma Save".to_string(),
-            val.to_string(),
-            TextFormatting::Line,
-        ));
-    }
-    if let Some(speed) = &c.speed {
-        lines.push((
-            "Speed".to_string(),
-            format!("{speed}"),
-            TextFormatting::NewLine,
-        ));
-    }
-    if let Some(size) = &c.size {
-        lines.push(("Size".to_string(), size.clone(), TextFormatting::NewLine));
-    }
-    if let Some(skills) = &c.skills {
-        lines.push((
-            "Skills".to_string(),
-            skills.to_string(),
-            TextFormatting::NewLine,
-        ));
-    }
-    if let Some(val) = &c.damage_vulnerabilities {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push((
-                "Damage Vulnerabilities".to_string(),
-                s,
-                TextFormatting::NewLine,
-            ));
-        }
-    }
-    if let Some(val) = &c.damage_resistances {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push(("Damage Resistance".to_string(), s, TextFormatting::NewLine));
-        }
-    }
-    if let Some(val) = &c.damage_immunities {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push(("Damage Immunities".to_string(), s, TextFormatting::NewLine));
-        }
-    }
-    if let Some(val) = &c.condition_immunities {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push((
-                "Condition Immunities".to_string(),
-                s,
-                TextFormatting::NewLine,
-            ));
-        }
-    }
-    if let Some(val) = &c.senses {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push(("Senses".to_string(), s, TextFormatting::NewLine));
-        }
-    }
-    if let Some(val) = &c.languages {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push(("Languages".to_string(), s, TextFormatting::NewLine));
-        }
-    }
-    if let Some(val) = &c.challenge_rating {
-        let s = val.to_string();
-        if !s.is_empty() {
-            lines.push(("Challenge Rating".to_string(), s, TextFormatting::NewLine));
-        }
-    }
-    if let Some(actions) = &c.actions {
-        lines.push((
-            "Actions".to_string(),
-            actions
-                .iter()
-                .map(|a| a.to_string())
-                .collect::<Vec<_>>()
-                .join("\n--------------------------------\n"),
-            TextFormatting::NewLine,
-        ));
-    }
-    if let Some(legendary_actions) = &c.legendary_actions {
-        lines.push((
-            "Legendary Actions".to_string(),
-            legendary_actions
-                .iter()
-                .map(|a| a.to_string())
-                .collect::<Vec<_>>()
-                .join("\n--------------------------------\n"),
-            TextFormatting::NewLine,
-        ));
-    }
-    if let Some(reactions) = &c.reactions {
-        lines.push((
-            "Reactions".to_string(),
-            reactions
-                .iter()
-                .map(|a| a.to_string())
-                .collect::<Vec<_>>()
-                .join("\n--------------------------------\n"),
-            TextFormatting::NewLine,
-        ));
-    }
-    if let Some(special_abilities) = &c.special_abilities {
-        lines.push((
-            "Special Abilities".to_string(),
-            special_abilities
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join("\n--------------------------------\n"),
-            TextFormatting::NewLine,
-        ));
-    }
-    lines
-}
-
 const fn alternate_colors(i: usize) -> Color {
-    if i % 2 == 0 {
+    if i.is_multiple_of(2) {
         NORMAL_ROW_BG
     } else {
         ALT_ROW_BG_COLOR
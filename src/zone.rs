@@ -0,0 +1,94 @@
+use crate::creature::CreatureItem;
+use std::fmt;
+
+/// An effect on the battlefield rather than on a creature, like Spirit
+/// Guardians or Wall of Fire. It lasts its rounds counted from the turn of
+/// the creature that created it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneEffect {
+    pub name: String,
+    /// The id of the creature that created it.
+    pub owner: u64,
+    pub owner_name: String,
+    /// The round it was created in.
+    pub round: u32,
+    pub rounds: u32,
+    /// Shown at the start of every turn while it lasts.
+    pub reminder: Option<String>,
+}
+
+impl ZoneEffect {
+    pub fn new(name: &str, owner: &CreatureItem, round: u32, rounds: u32) -> Self {
+        ZoneEffect {
+            name: name.to_string(),
+            owner: owner.id,
+            owner_name: owner.name.clone(),
+            round,
+            rounds,
+            reminder: None,
+        }
+    }
+
+    /// The round in which it ends, at the start of the owner's turn.
+    pub fn ends(&self) -> u32 {
+        self.round + self.rounds
+    }
+
+    /// Before the round it was created in, e.g. while setting up, all of its
+    /// rounds are left.
+    pub fn rounds_left(&self, round: u32) -> u32 {
+        self.ends().saturating_sub(round.max(self.round))
+    }
+
+    /// Whether it is over at the start of the turn of the creature with the
+    /// id. Once the round has passed it is over whoever's turn it is, so it
+    /// still ends when the owner is gone.
+    pub fn expired(&self, round: u32, current: u64) -> bool {
+        round > self.ends() || (round == self.ends() && current == self.owner)
+    }
+
+    /// E.g. "Spirit Guardians (Cleric, 3 rounds left)".
+    pub fn summary(&self, round: u32) -> String {
+        let left = self.rounds_left(round);
+        format!(
+            "{} ({}, {left} round{} left)",
+            self.name,
+            self.owner_name,
+            if left == 1 { "" } else { "s" }
+        )
+    }
+}
+
+impl fmt::Display for ZoneEffect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}, {} round{})",
+            self.name,
+            self.owner_name,
+            self.rounds,
+            if self.rounds == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Remove the zones that are over at the start of the turn and return them.
+pub fn expire_zones(zones: &mut Vec<ZoneEffect>, round: u32, current: u64) -> Vec<ZoneEffect> {
+    let (expired, active) = std::mem::take(zones)
+        .into_iter()
+        .partition(|zone| zone.expired(round, current));
+    *zones = active;
+    expired
+}
+
+/// The reminders of the zones, e.g. "Spirit Guardians: WIS save DC 15 or 3d8
+/// radiant".
+pub fn zone_reminders(zones: &[ZoneEffect]) -> Vec<String> {
+    zones
+        .iter()
+        .filter_map(|zone| {
+            let reminder = zone.reminder.as_ref()?;
+            Some(format!("{}: {reminder}", zone.name))
+        })
+        .collect()
+}
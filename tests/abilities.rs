@@ -0,0 +1,69 @@
+use std::fs;
+
+fn load_mock_creature_json(file: &str) -> String {
+    fs::read_to_string(format!("tests/fixtures/{}", file))
+        .unwrap_or_else(|_| panic!("Failed to read the json file: {}", file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wtii::abilities::{parse_limited_uses, Recovery, Rest};
+    use wtii::api::parse_json_response;
+    use wtii::creature::CreatureItem;
+
+    #[test]
+    fn test_parse_per_day_in_name() {
+        let uses = parse_limited_uses("Legendary Resistance (3/Day)", "Some text");
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].label, "Legendary Resistance");
+        assert_eq!(uses[0].max, 3);
+        assert_eq!(uses[0].recovery, Recovery::LongRest);
+    }
+
+    #[test]
+    fn test_parse_recharge_after_rest() {
+        let uses = parse_limited_uses(
+            "Relentless (Recharges after a Short or Long Rest)",
+            "Some text",
+        );
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].recovery, Recovery::ShortRest);
+        assert!(parse_limited_uses("Breath (Recharge 5-6)", "Some text").is_empty());
+    }
+
+    #[test]
+    fn test_parse_innate_spellcasting() {
+        let desc = "It can cast the following spells:\n\nat will: feather fall\n\n\
+                    5/day each: lightning bolt, ice storm\n\n1/day: divination";
+        let uses = parse_limited_uses("Innate Spellcasting", desc);
+        let labels: Vec<&str> = uses.iter().map(|u| u.label.as_str()).collect();
+        assert_eq!(labels, vec!["lightning bolt", "ice storm", "divination"]);
+        assert_eq!(uses[0].max, 5);
+        assert_eq!(uses[2].max, 1);
+    }
+
+    #[test]
+    fn test_rest_resets_uses() {
+        let data = load_mock_creature_json("lots_of_resp.json");
+        let creatures = parse_json_response(&data).unwrap();
+        let dragon = creatures
+            .iter()
+            .find(|c| c.name == "Adult Wind Dragon")
+            .unwrap();
+        let mut creature = CreatureItem::new_npc(dragon);
+        let labels: Vec<&str> = creature
+            .limited_uses()
+            .iter()
+            .map(|u| u.label.as_str())
+            .collect();
+        assert_eq!(labels, ["lightning bolt", "Legendary Resistance"]);
+        for limited_use in creature.limited_uses_mut() {
+            assert!(limited_use.spend());
+        }
+        creature.rest(Rest::Short);
+        assert!(creature.limited_uses().iter().all(|u| u.remaining < u.max));
+        creature.rest(Rest::Long);
+        assert!(creature.limited_uses().iter().all(|u| u.remaining == u.max));
+    }
+}
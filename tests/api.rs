@@ -3,20 +3,30 @@ use std::fs;
 
 fn load_mock_creature_json(file: &str) -> String {
     fs::read_to_string(format!("tests/fixtures/{}", file))
-        .expect(format!("Failed to read the json file: {}", file).as_str())
+        .unwrap_or_else(|_| panic!("Failed to read the json file: {}", file))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mockito;
     use reqwest::{Error, Response};
-    use wtii::api::{parse_json_response, search_for_creature, ApiCall};
+    use wtii::api::{
+        fetch_encounter_monsters, fetch_spell_list, parse_json_response,
+        parse_reference_json_response, parse_spell_json_response, search_for_creature,
+        search_for_creatures_by_cr, search_for_spell, search_reference, ApiCall, MonsterFilter,
+        MonsterFilterApiCall, ReferenceApiCall, SpellApiCall,
+    };
+    use wtii::encounter::{EncounterMonster, EncounterTemplate};
+    use wtii::reference::ReferenceKind;
 
     struct MockMonsterSearchOneCreature;
     struct MockMonsterSearchMultipleCreatures;
     struct MockMonsterSearchLotsOfCreatures;
     struct MockMonsterSearchTimeoutError;
+    struct MockMonsterFilter;
+    struct MockSpellSearch;
+    struct MockSpellSearchTimeoutError;
+    struct MockReferenceSearch;
 
     impl ApiCall for MockMonsterSearchOneCreature {
         async fn monster_search(&self, name: &str) -> Result<Response, Error> {
@@ -92,6 +102,129 @@ mod tests {
         }
     }
 
+    impl MonsterFilterApiCall for MockMonsterFilter {
+        async fn monster_filter(&self, filter: &MonsterFilter) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/monsters/?{}", filter.query());
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(load_mock_creature_json("multiple_creatures_response.json"))
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+    }
+
+    impl SpellApiCall for MockSpellSearch {
+        async fn spell_search(&self, name: &str) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/spells/?search={}", name);
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(load_mock_creature_json("spell_search_response.json"))
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+
+        async fn spell_fetch(&self, slug: &str) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/spells/{}/", slug);
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(load_mock_creature_json("single_spell_response.json"))
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+    }
+
+    impl SpellApiCall for MockSpellSearchTimeoutError {
+        async fn spell_search(&self, name: &str) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/spells/?search={}", name);
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(408)
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+
+        async fn spell_fetch(&self, slug: &str) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/spells/{}/", slug);
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(408)
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+    }
+
+    impl ReferenceApiCall for MockReferenceSearch {
+        async fn condition_search(&self, name: &str) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/conditions/?search={}", name);
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(load_mock_creature_json("condition_search_response.json"))
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+
+        async fn section_search(&self, name: &str) -> Result<Response, Error> {
+            let endpoint: &str = &format!("/sections/?search={}", name);
+            let mut server = mockito::Server::new_async().await;
+            let _m = server
+                .mock("GET", endpoint)
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body(load_mock_creature_json("section_search_response.json"))
+                .create_async()
+                .await;
+            let client = Client::new();
+            client
+                .get(format!("{}{}", &server.url(), endpoint))
+                .send()
+                .await
+        }
+    }
+
     #[tokio::test]
     async fn test_search_for_one_creature_ok() {
         let mock = MockMonsterSearchOneCreature;
@@ -120,10 +253,118 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[tokio::test]
+    async fn test_search_for_creatures_by_cr_ok() {
+        let filter = MonsterFilter {
+            cr_min: 0.25,
+            cr_max: 2.0,
+            creature_type: Some("humanoid".to_string()),
+        };
+        assert_eq!(
+            filter.query(),
+            "cr__gte=0.25&cr__lte=2&type__iexact=humanoid&limit=100"
+        );
+        let res = search_for_creatures_by_cr(&MockMonsterFilter, &filter).await;
+        assert!(!res.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_encounter_monsters() {
+        let template = EncounterTemplate {
+            name: "Ambush".to_string(),
+            monsters: vec![EncounterMonster {
+                name: "mock-call".to_string(),
+                count: 3,
+            }],
+            ..Default::default()
+        };
+        let monsters = fetch_encounter_monsters(&MockMonsterSearchOneCreature, &template)
+            .await
+            .unwrap();
+        assert_eq!(monsters.len(), 1);
+        assert_eq!(monsters[0].1, 3);
+
+        let res = fetch_encounter_monsters(&MockMonsterSearchTimeoutError, &template).await;
+        assert!(res.is_err());
+    }
+
     #[test]
     fn test_parse_json_response() {
         let data: String = load_mock_creature_json("single_creature_response.json");
-        let res = parse_json_response(&*data);
+        let res = parse_json_response(&data);
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_search_for_spell_ok() {
+        let mock = MockSpellSearch;
+        let res = search_for_spell(&mock, "fire").await.unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].name, "Fireball");
+        assert_eq!(res[0].level_int, Some(3));
+        assert_eq!(res[0].school.as_deref(), Some("Evocation"));
+    }
+
+    #[tokio::test]
+    async fn test_search_for_spell_timeout_error() {
+        let mock = MockSpellSearchTimeoutError;
+        let res = search_for_spell(&mock, "mock-call").await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_spell_list_ok() {
+        let mock = MockSpellSearch;
+        let spell_list = vec!["https://api.open5e.com/v1/spells/fireball/".to_string()];
+        let res = fetch_spell_list(&mock, &spell_list).await;
+        assert!(res.errors.is_empty());
+        assert_eq!(res.spells.len(), 1);
+        assert_eq!(res.spells[0].casting_time.as_deref(), Some("1 action"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_spell_list_timeout_error() {
+        let mock = MockSpellSearchTimeoutError;
+        let spell_list = vec!["https://api.open5e.com/v1/spells/fireball/".to_string()];
+        let res = fetch_spell_list(&mock, &spell_list).await;
+        assert!(res.spells.is_empty());
+        assert_eq!(res.errors.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_spell_list_keeps_loaded_spells() {
+        let mock = MockSpellSearch;
+        let spell_list = vec![
+            "https://api.open5e.com/v1/spells/fireball/".to_string(),
+            String::new(),
+        ];
+        let res = fetch_spell_list(&mock, &spell_list).await;
+        assert_eq!(res.spells.len(), 1);
+        assert_eq!(res.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_spell_json_response() {
+        let data: String = load_mock_creature_json("spell_search_response.json");
+        let res = parse_spell_json_response(&data);
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_search_reference_ok() {
+        let mock = MockReferenceSearch;
+        let res = search_reference(&mock, "grappl").await.unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].name, "Grappled");
+        assert_eq!(res[0].kind, ReferenceKind::Condition);
+        assert_eq!(res[1].name, "Grappling");
+        assert_eq!(res[1].kind, ReferenceKind::Rule);
+    }
+
+    #[test]
+    fn test_parse_reference_json_response() {
+        let data: String = load_mock_creature_json("section_search_response.json");
+        let res = parse_reference_json_response(&data, ReferenceKind::Rule);
         assert!(res.is_ok());
     }
 }
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use wtii::area_effect::{defense_against, resolve_save, AreaEffect, Defense};
+    use wtii::checks::{ability_modifier, Ability};
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, Status};
+
+    fn fireball() -> AreaEffect {
+        AreaEffect {
+            ability: Ability::Dexterity,
+            dc: 15,
+            damage: 28,
+            damage_type: Some("fire".to_string()),
+            half_on_success: true,
+        }
+    }
+
+    #[test]
+    fn test_ability_modifier() {
+        assert_eq!(ability_modifier(10), 0);
+        assert_eq!(ability_modifier(15), 2);
+        assert_eq!(ability_modifier(9), -1);
+        assert_eq!(ability_modifier(1), -5);
+        assert_eq!("dex".parse::<Ability>(), Ok(Ability::Dexterity));
+        assert_eq!("Wisdom".parse::<Ability>(), Ok(Ability::Wisdom));
+        assert!("st".parse::<Ability>().is_err());
+    }
+
+    #[test]
+    fn test_save_bonus_falls_back_to_modifier() {
+        let creature = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            dexterity: Some(14),
+            wisdom_save: Some(5),
+            wisdom: Some(10),
+            ..Default::default()
+        });
+        assert_eq!(Ability::Dexterity.save_bonus(&creature), 2);
+        assert_eq!(Ability::Wisdom.save_bonus(&creature), 5);
+    }
+
+    #[test]
+    fn test_resolve_save() {
+        let mut creature = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            dexterity: Some(14),
+            ..Default::default()
+        });
+        let result = resolve_save(&creature, &fireball(), 13);
+        assert!(result.success);
+        assert_eq!(result.damage, 14);
+
+        creature.damage_resistances = Some("cold, fire".to_string());
+        let result = resolve_save(&creature, &fireball(), 2);
+        assert!(!result.success);
+        assert_eq!(result.defense, Some(Defense::Resistant));
+        assert_eq!(result.damage, 14);
+
+        creature.damage_immunities = Some("fire".to_string());
+        let result = resolve_save(&creature, &fireball(), 2);
+        assert_eq!(result.damage, 0);
+    }
+
+    #[test]
+    fn test_conditional_defenses_are_ignored() {
+        let mut creature = CreatureItem::new_npc(&ApiCreatureSearchItem::default());
+        creature.damage_resistances =
+            Some("cold; bludgeoning, piercing, and slashing from nonmagical attacks".to_string());
+        assert_eq!(defense_against(&creature, "cold"), Some(Defense::Resistant));
+        assert_eq!(defense_against(&creature, "slashing"), None);
+
+        creature.damage_resistances = Some("acid, cold, fire, lightning, and thunder".to_string());
+        assert_eq!(
+            defense_against(&creature, "thunder"),
+            Some(Defense::Resistant)
+        );
+        assert_eq!(defense_against(&creature, "necrotic"), None);
+    }
+
+    #[test]
+    fn test_take_damage_and_heal() {
+        let mut creature = CreatureItem::new_npc(&ApiCreatureSearchItem::default());
+        creature.take_damage(100);
+        assert_eq!(creature.hit_points, 0);
+        assert_eq!(creature.status, Status::Dead);
+        creature.heal(100);
+        assert_eq!(creature.hit_points, creature.max_hit_points);
+        assert_eq!(creature.status, Status::Alive);
+
+        // The players keep track of their own HP
+        let mut player = CreatureItem::new_player("Fighter", None);
+        player.take_damage(100);
+        assert_eq!(player.status, Status::Alive);
+        assert_eq!(player.hit_points, 1);
+    }
+}
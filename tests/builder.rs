@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use wtii::builder::{suggest_encounters, xp_budget};
+    use wtii::creature::ApiCreatureSearchItem;
+    use wtii::difficulty::Difficulty;
+
+    fn candidate(name: &str, cr: &str) -> ApiCreatureSearchItem {
+        ApiCreatureSearchItem {
+            name: name.to_string(),
+            challenge_rating: Some(cr.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_xp_budget() {
+        let party = [3, 3, 3, 3];
+        assert_eq!(xp_budget(&party, Difficulty::Easy), 300);
+        assert_eq!(xp_budget(&party, Difficulty::Deadly), 1600);
+    }
+
+    #[test]
+    fn test_suggest_encounters() {
+        let candidates = vec![
+            candidate("Goblin", "1/4"),
+            candidate("Bugbear", "1"),
+            candidate("Goblin", "1/4"),
+            candidate("Nameless", "unknown"),
+        ];
+        let party = [3, 3, 3, 3];
+        let plans = suggest_encounters(&candidates, &party, Difficulty::Medium, 10);
+
+        assert!(!plans.is_empty());
+        for plan in &plans {
+            assert_eq!(plan.summary.difficulty, Difficulty::Medium);
+            assert!(plan.monsters.iter().all(|m| m.name != "Nameless"));
+        }
+        // 6 goblins are 300 XP, doubled for the group
+        let goblins = plans
+            .iter()
+            .find(|plan| plan.monsters.len() == 1 && plan.monsters[0].name == "Goblin")
+            .unwrap();
+        assert_eq!(goblins.monsters[0].count, 6);
+        assert_eq!(goblins.summary.adjusted_xp, 600);
+
+        let template = goblins.to_template("Goblin ambush");
+        assert_eq!(template.monsters[0].count, 6);
+        assert_eq!(template.file_name(), "goblin-ambush.yml");
+    }
+
+    #[test]
+    fn test_suggest_encounters_respects_limit() {
+        let candidates = vec![
+            candidate("Goblin", "1/4"),
+            candidate("Bugbear", "1"),
+            candidate("Hobgoblin", "1/2"),
+        ];
+        let plans = suggest_encounters(&candidates, &[5, 5, 5], Difficulty::Hard, 2);
+        assert_eq!(plans.len(), 2);
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use wtii::checks::{Ability, Check, CheckRoll, RollMode, Skill};
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, Skills};
+
+    fn creature() -> CreatureItem {
+        CreatureItem::new_npc(&ApiCreatureSearchItem {
+            dexterity: Some(14),
+            wisdom: Some(12),
+            wisdom_save: Some(4),
+            perception: Some(5),
+            skills: Some(Skills {
+                stealth: Some(6),
+                sleight_of_hand: Some(4),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_skills_deserialize_every_skill() {
+        let skills: Skills = serde_json::from_str(
+            r#"{"acrobatics": 3, "animal_handling": 2, "sleight_of_hand": 5, "survival": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(skills.acrobatics, Some(3));
+        assert_eq!(skills.animal_handling, Some(2));
+        assert_eq!(skills.sleight_of_hand, Some(5));
+        assert_eq!(
+            skills.to_string(),
+            "Acrobatics: 3, Animal Handling: 2, Sleight Of Hand: 5, Survival: 1"
+        );
+    }
+
+    #[test]
+    fn test_check_bonuses() {
+        let creature = creature();
+        assert_eq!(Skill::Stealth.bonus(&creature), 6);
+        assert_eq!(Skill::Acrobatics.bonus(&creature), 2);
+        assert_eq!(Skill::Perception.bonus(&creature), 5);
+        assert_eq!(Skill::Insight.bonus(&creature), 1);
+        assert_eq!(Check::Save(Ability::Wisdom).bonus(&creature), 4);
+        assert_eq!(Check::Ability(Ability::Wisdom).bonus(&creature), 1);
+        assert_eq!(Check::all().len(), 30);
+    }
+
+    #[test]
+    fn test_check_roll_modes() {
+        let check = Check::Skill(Skill::Stealth);
+        let normal = CheckRoll::from_rolls(check, RollMode::Normal, 6, (7, 15));
+        assert_eq!(normal.rolls, vec![7]);
+        assert_eq!(normal.total, 13);
+
+        let advantage = CheckRoll::from_rolls(check, RollMode::Advantage, 6, (7, 15));
+        assert_eq!(advantage.total, 21);
+        assert_eq!(
+            advantage.to_string(),
+            "Stealth (DEX): [7, 15] +6 = 21 (Advantage)"
+        );
+
+        let disadvantage = CheckRoll::from_rolls(check, RollMode::Disadvantage, 6, (7, 15));
+        assert_eq!(disadvantage.total, 13);
+        assert_eq!(RollMode::Disadvantage.next(), RollMode::Normal);
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use wtii::cli::{parse_args, Cli, CliCommand};
+    use wtii::creature::{get_config_file_location, set_config_file_location};
+    use wtii::stat_block::StatBlockFormat;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_no_arguments_opens_the_tracker() {
+        assert_eq!(
+            parse_args(args("")).unwrap(),
+            Cli {
+                config: None,
+                command: CliCommand::Tui {
+                    load: None,
+                    serve: None,
+                    players: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_and_config() {
+        assert_eq!(
+            parse_args(args("--load ambush.yml --config party.yml")).unwrap(),
+            Cli {
+                config: Some("party.yml".to_string()),
+                command: CliCommand::Tui {
+                    load: Some("ambush.yml".to_string()),
+                    serve: None,
+                    players: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn test_search_joins_the_name() {
+        let cli = parse_args(args("--config party.yml search goblin boss")).unwrap();
+        assert_eq!(cli.config.as_deref(), Some("party.yml"));
+        assert_eq!(
+            cli.command,
+            CliCommand::Search {
+                name: "goblin boss".to_string(),
+                format: StatBlockFormat::Text,
+            }
+        );
+    }
+
+    #[test]
+    fn test_search_format() {
+        assert_eq!(
+            parse_args(args("search --format json goblin"))
+                .unwrap()
+                .command,
+            CliCommand::Search {
+                name: "goblin".to_string(),
+                format: StatBlockFormat::Json,
+            }
+        );
+        assert!(parse_args(args("search --format yaml goblin")).is_err());
+        assert!(parse_args(args("--format json roll d20")).is_err());
+        assert!(parse_args(args("--format json")).is_err());
+    }
+
+    #[test]
+    fn test_roll() {
+        assert_eq!(
+            parse_args(args("roll 2d6 + 3")).unwrap().command,
+            CliCommand::Roll {
+                expr: "2d6+3".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_serve() {
+        assert_eq!(
+            parse_args(args("--serve")).unwrap().command,
+            CliCommand::Tui {
+                load: None,
+                serve: Some(7878),
+                players: None,
+            }
+        );
+        assert_eq!(
+            parse_args(args("--serve 9000 --load ambush.yml"))
+                .unwrap()
+                .command,
+            CliCommand::Tui {
+                load: Some("ambush.yml".to_string()),
+                serve: Some(9000),
+                players: None,
+            }
+        );
+        assert!(parse_args(args("--serve search goblin")).is_err());
+    }
+
+    #[test]
+    fn test_players() {
+        assert_eq!(
+            parse_args(args("--players")).unwrap().command,
+            CliCommand::Tui {
+                load: None,
+                serve: None,
+                players: Some(7879),
+            }
+        );
+        assert_eq!(
+            parse_args(args("--serve --players 9001")).unwrap().command,
+            CliCommand::Tui {
+                load: None,
+                serve: Some(7878),
+                players: Some(9001),
+            }
+        );
+        assert!(parse_args(args("--players roll d20")).is_err());
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(parse_args(args("-h")).unwrap().command, CliCommand::Help);
+        assert_eq!(
+            parse_args(args("--help")).unwrap().command,
+            CliCommand::Help
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse_args(args("search")).is_err());
+        assert!(parse_args(args("roll")).is_err());
+        assert!(parse_args(args("--config")).is_err());
+        assert!(parse_args(args("--verbose")).is_err());
+        assert!(parse_args(args("fight")).is_err());
+        assert!(parse_args(args("--load ambush.yml roll d20")).is_err());
+    }
+
+    #[test]
+    fn test_config_file_override() {
+        set_config_file_location("/tmp/party.yml");
+        assert_eq!(
+            get_config_file_location().as_deref(),
+            Some("/tmp/party.yml")
+        );
+    }
+}
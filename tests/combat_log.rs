@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use wtii::combat_log::{CombatLog, EventKind, ExportFormat};
+    use wtii::creature::{CreatureItem, CreatureList};
+
+    fn creatures() -> CreatureList {
+        CreatureList::new(vec![
+            CreatureItem::new_player("Goblin", None),
+            CreatureItem::new_player("Goblin", None),
+        ])
+    }
+
+    #[test]
+    fn test_manual_hit_point_changes_are_merged() {
+        let creature_list = creatures();
+        let (first, second) = (&creature_list.items[0], &creature_list.items[1]);
+        let mut log = CombatLog::default();
+
+        log.log_hit_points(1, first, -1, None);
+        log.log_hit_points(1, first, -1, None);
+        log.log_hit_points(1, first, -1, None);
+        log.log_hit_points(1, first, 1, None);
+        log.log_hit_points(1, second, -4, Some("DEX save DC 15".to_string()));
+        log.log_hit_points(1, second, -1, None);
+        let events: Vec<String> = log.events().iter().map(|e| e.kind.to_string()).collect();
+        assert_eq!(
+            events,
+            vec![
+                "Goblin takes 2 damage",
+                "Goblin takes 4 damage from DEX save DC 15",
+                "Goblin takes 1 damage",
+            ]
+        );
+
+        // Healing back to where it started leaves nothing to log
+        log.log_hit_points(1, second, 1, None);
+        assert_eq!(log.events().len(), 2);
+    }
+
+    fn sample_log() -> CombatLog {
+        let mut log = CombatLog::default();
+        let goblin = || "Goblin".to_string();
+        log.push(
+            0,
+            0,
+            EventKind::Roll {
+                creature: goblin(),
+                result: "Stealth (DEX): [12] +6 = 18".to_string(),
+            },
+            None,
+        );
+        log.push(3725, 1, EventKind::TurnStart { creature: goblin() }, None);
+        log.push(
+            3730,
+            1,
+            EventKind::ConditionApplied {
+                creature: goblin(),
+                condition: "Prone".to_string(),
+            },
+            None,
+        );
+        log.push(3800, 2, EventKind::Death { creature: goblin() }, None);
+        log
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let log = sample_log();
+        assert_eq!(
+            log.events()[1].to_string(),
+            "[01:02:05] Round 1: Goblin starts their turn"
+        );
+        assert_eq!(
+            log.to_markdown(),
+            "# Combat Log\n\
+            \n## Before combat\n\n\
+            - 00:00:00 Goblin rolls Stealth (DEX): [12] +6 = 18\n\
+            \n## Round 1\n\n\
+            - 01:02:05 Goblin starts their turn\n\
+            - 01:02:10 Goblin is Prone\n\
+            \n## Round 2\n\n\
+            - 01:03:20 Goblin drops to 0 HP\n"
+        );
+    }
+
+    #[test]
+    fn test_json_export() {
+        let log = sample_log();
+        let json: serde_json::Value = serde_json::from_str(&log.to_json().unwrap()).unwrap();
+        assert_eq!(json[2]["type"], "condition_applied");
+        assert_eq!(json[2]["condition"], "Prone");
+        assert_eq!(json[2]["round"], 1);
+        assert_eq!(json[2]["timestamp"], 3730);
+        assert!(json[2].get("creature_id").is_none());
+
+        let dir = std::env::temp_dir().join("wtii-test-export-log");
+        let path = log.export(&dir, ExportFormat::Json).unwrap();
+        assert_eq!(path, dir.join("combat-log-0.json"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), log.to_json().unwrap());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_hidden_creatures_are_not_exported() {
+        let mut creature_list = creatures();
+        creature_list.items[0].name = "Assassin".to_string();
+        creature_list.items[0].hidden = true;
+        let mut log = CombatLog::default();
+
+        let assassin = &creature_list.items[0];
+        let kind = EventKind::TurnStart {
+            creature: assassin.name.clone(),
+        };
+        log.log_creature(1, assassin, kind);
+        log.log_hit_points(1, &creature_list.items[1], -5, None);
+
+        creature_list.items[0].hidden = false;
+        let assassin = &creature_list.items[0];
+        let kind = EventKind::Revealed {
+            creature: assassin.name.clone(),
+        };
+        log.log_creature(1, assassin, kind);
+
+        assert_eq!(log.events().len(), 3);
+        assert!(log.events()[0]
+            .to_string()
+            .ends_with("Assassin starts their turn (hidden)"));
+        let public: Vec<String> = log.public_events().map(|e| e.kind.to_string()).collect();
+        assert_eq!(public, ["Goblin takes 5 damage", "Assassin appears"]);
+        assert!(!log.to_markdown().contains("Assassin starts"));
+        let json: serde_json::Value = serde_json::from_str(&log.to_json().unwrap()).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[1]["type"], "revealed");
+    }
+}
@@ -0,0 +1,357 @@
+use reqwest::header::{CONTENT_TYPE, HOST, ORIGIN};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use wtii::control::{serve, ControlRequest};
+use wtii::ui::App;
+
+/// A tracker without the players of `default.yml` and the URL of its control API.
+async fn start() -> (App, mpsc::UnboundedReceiver<ControlRequest>, String) {
+    wtii::creature::set_config_file_location("tests/fixtures/missing.yml");
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(serve(listener, tx));
+    (App::default(), rx, url)
+}
+
+/// Send the request while the tracker handles the command it turns into.
+async fn send(
+    app: &mut App,
+    rx: &mut mpsc::UnboundedReceiver<ControlRequest>,
+    request: RequestBuilder,
+) -> (StatusCode, Value) {
+    let response = tokio::spawn(async move {
+        let response = request.send().await.unwrap();
+        (response.status(), response.json::<Value>().await.unwrap())
+    });
+    app.handle_control(rx.recv().await.unwrap());
+    response.await.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    use serde_json::json;
+    use wtii::control::{
+        route, route_players, serve_players, ControlCommand, ControlError, HitPointsChange,
+        RequestHeaders, Target,
+    };
+    use wtii::keybindings::KeyBindings;
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("GET", "/state", ""), Ok(ControlCommand::State));
+        assert_eq!(
+            route("POST", "/next-turn/", ""),
+            Ok(ControlCommand::NextTurn)
+        );
+        assert_eq!(
+            route("POST", "/damage", r#"{"target": "Goblin", "amount": 7}"#),
+            Ok(ControlCommand::Damage(HitPointsChange {
+                target: Some(Target::Name("Goblin".to_string())),
+                amount: 7,
+            }))
+        );
+        assert_eq!(
+            route("POST", "/heal", r#"{"amount": 3}"#),
+            Ok(ControlCommand::Heal(HitPointsChange {
+                target: None,
+                amount: 3,
+            }))
+        );
+        assert!(matches!(
+            route("POST", "/damage", "{}"),
+            Err(ControlError::BadRequest(_))
+        ));
+        assert_eq!(route("GET", "/damage", ""), Err(ControlError::NotFound));
+        assert_eq!(route("GET", "/players", ""), Err(ControlError::NotFound));
+    }
+
+    #[test]
+    fn test_route_players_only_reads() {
+        assert_eq!(
+            route_players("GET", "/players", ""),
+            Ok(ControlCommand::State)
+        );
+        assert_eq!(
+            route_players("GET", "/players.json/", ""),
+            Ok(ControlCommand::State)
+        );
+        assert_eq!(
+            route_players("GET", "/state", ""),
+            Err(ControlError::NotFound)
+        );
+        assert_eq!(
+            route_players("POST", "/next-turn", ""),
+            Err(ControlError::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_creature_and_next_turn() {
+        let (mut app, mut rx, url) = start().await;
+        let client = Client::new();
+
+        let body = json!({"name": "Goblin", "hit_points": 7, "initiative": 12});
+        let (status, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/creatures")).json(&body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let body = json!({"name": "Fighter", "player": true, "initiative": 15});
+        let (_, state_after) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/creatures")).json(&body),
+        )
+        .await;
+        assert_eq!(state["creatures"].as_array().unwrap().len(), 1);
+        assert_eq!(state_after["creatures"][0]["name"], "Fighter");
+        assert_eq!(state_after["creatures"][1]["hit_points"], 7);
+        assert_eq!(state_after["current"], Value::Null);
+
+        let next_turn = client
+            .post(format!("{url}/next-turn"))
+            .header(CONTENT_TYPE, "application/json");
+        let (_, state) = send(&mut app, &mut rx, next_turn).await;
+        assert_eq!(state["current"], state["creatures"][0]["id"]);
+        let (_, state) = send(&mut app, &mut rx, client.get(format!("{url}/state"))).await;
+        assert_eq!(state["current"], state["creatures"][0]["id"]);
+    }
+
+    #[tokio::test]
+    async fn test_damage_and_heal() {
+        let (mut app, mut rx, url) = start().await;
+        let client = Client::new();
+
+        let body = json!({"name": "Ogre", "hit_points": 59, "initiative": 8});
+        let (_, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/creatures")).json(&body),
+        )
+        .await;
+        let id = state["creatures"][0]["id"].clone();
+
+        let body = json!({"target": id, "amount": 20});
+        let (_, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/damage")).json(&body),
+        )
+        .await;
+        assert_eq!(state["creatures"][0]["hit_points"], 39);
+
+        let body = json!({"target": "ogre", "amount": 100});
+        let (_, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/heal")).json(&body),
+        )
+        .await;
+        assert_eq!(state["creatures"][0]["hit_points"], 59);
+
+        let body = json!({"target": "Ogre", "amount": 80});
+        let (_, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/damage")).json(&body),
+        )
+        .await;
+        assert_eq!(state["creatures"][0]["hit_points"], 0);
+        assert_eq!(state["creatures"][0]["dead"], true);
+
+        // Nobody has the turn yet
+        let body = json!({"amount": 1});
+        let (status, error) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/damage")).json(&body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["error"], "No creature has the turn");
+
+        let body = json!({"name": "Fighter", "player": true});
+        send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/creatures")).json(&body),
+        )
+        .await;
+        let body = json!({"target": "Fighter", "amount": 5});
+        let (_, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/damage")).json(&body),
+        )
+        .await;
+        let fighter = &state["creatures"][0];
+        assert_eq!(fighter["untracked_hp"], true);
+        assert_eq!(fighter["dead"], false);
+    }
+
+    #[tokio::test]
+    async fn test_state_reports_the_turn_holder_while_peeking() {
+        let (mut app, mut rx, url) = start().await;
+        let client = Client::new();
+        for (name, initiative) in [("Fighter", 15), ("Goblin", 12)] {
+            let body = json!({"name": name, "hit_points": 10, "initiative": initiative});
+            send(
+                &mut app,
+                &mut rx,
+                client.post(format!("{url}/creatures")).json(&body),
+            )
+            .await;
+        }
+        let next_turn = client
+            .post(format!("{url}/next-turn"))
+            .header(CONTENT_TYPE, "application/json");
+        let (_, state) = send(&mut app, &mut rx, next_turn).await;
+        let fighter = state["creatures"][0]["id"].clone();
+        assert_eq!(state["current"], fighter);
+
+        // The DM peeks at the Goblin during the Fighter's turn
+        let peek = KeyBindings::default().peek_down;
+        app.handle_key(KeyEvent::from(KeyCode::Char(peek))).await;
+
+        let (_, state) = send(&mut app, &mut rx, client.get(format!("{url}/state"))).await;
+        assert_eq!(state["current"], fighter);
+        let body = json!({"amount": 4});
+        let (_, state) = send(
+            &mut app,
+            &mut rx,
+            client.post(format!("{url}/damage")).json(&body),
+        )
+        .await;
+        assert_eq!(state["creatures"][0]["hit_points"], 6);
+        assert_eq!(state["creatures"][1]["hit_points"], 10);
+    }
+
+    #[tokio::test]
+    async fn test_player_view_listener() {
+        wtii::creature::set_config_file_location("tests/fixtures/missing.yml");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tokio::spawn(serve_players(listener, tx));
+        let mut app = App::default();
+        let client = Client::new();
+
+        let (status, view) =
+            send(&mut app, &mut rx, client.get(format!("{url}/players.json"))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(view["creatures"].is_array());
+
+        let response = client
+            .post(format!("{url}/next-turn"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_route() {
+        let (_app, _rx, url) = start().await;
+        let response = Client::new()
+            .get(format!("{url}/nothing"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_request_headers() {
+        let local = |origin: Option<&str>| RequestHeaders {
+            host: Some("localhost:7878".to_string()),
+            origin: origin.map(str::to_string),
+            ..RequestHeaders::default()
+        };
+        assert_eq!(local(None).check_origin(7878), Ok(()));
+        assert_eq!(
+            local(Some("http://127.0.0.1:7878")).check_origin(7878),
+            Ok(())
+        );
+        for origin in ["https://evil.example", "http://localhost:7879", "null"] {
+            assert!(matches!(
+                local(Some(origin)).check_origin(7878),
+                Err(ControlError::Forbidden(_))
+            ));
+        }
+        for host in [
+            None,
+            Some("evil.example:7878"),
+            Some("localhost.evil.example"),
+        ] {
+            let headers = RequestHeaders {
+                host: host.map(str::to_string),
+                ..RequestHeaders::default()
+            };
+            assert!(matches!(
+                headers.check_origin(7878),
+                Err(ControlError::Forbidden(_))
+            ));
+        }
+
+        assert_eq!(local(None).check_content_type("GET"), Ok(()));
+        assert_eq!(
+            local(None).check_content_type("POST"),
+            Err(ControlError::UnsupportedMediaType)
+        );
+        let json = RequestHeaders {
+            content_type: Some("application/json; charset=utf-8".to_string()),
+            ..RequestHeaders::default()
+        };
+        assert_eq!(json.check_content_type("POST"), Ok(()));
+        let form = RequestHeaders {
+            content_type: Some("text/plain".to_string()),
+            content_length: 2,
+            ..RequestHeaders::default()
+        };
+        assert_eq!(
+            form.check_content_type("GET"),
+            Err(ControlError::UnsupportedMediaType)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_browser_requests_are_refused() {
+        let (_app, mut rx, url) = start().await;
+        let client = Client::new();
+
+        let response = client
+            .post(format!("{url}/next-turn"))
+            .header(CONTENT_TYPE, "application/json")
+            .header(ORIGIN, "https://evil.example")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = client
+            .get(format!("{url}/state"))
+            .header(HOST, "evil.example")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = client
+            .post(format!("{url}/damage"))
+            .header(CONTENT_TYPE, "text/plain")
+            .body(r#"{"amount": 1}"#)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(rx.try_recv().is_err());
+    }
+}
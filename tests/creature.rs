@@ -0,0 +1,249 @@
+#[cfg(test)]
+mod tests {
+    use wtii::creature::{players_from_yaml, ApiCreatureSearchItem, CreatureItem, CreatureList};
+    use yaml_rust2::YamlLoader;
+
+    fn creature_with_initiative(name: &str, initiative: i64) -> CreatureItem {
+        let mut creature = CreatureItem::new_player(name, None);
+        creature.initiative = Some(initiative);
+        creature
+    }
+
+    #[test]
+    fn test_start_turn_resets_action_economy() {
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 10));
+        creature_list.items[0].economy.reaction = true;
+        creature_list.items[0].economy.bonus_action = true;
+        assert_eq!(creature_list.items[0].economy.to_string(), "BR");
+
+        creature_list.start_turn(0);
+        assert_eq!(creature_list.items[0].economy.to_string(), "");
+    }
+
+    #[test]
+    fn test_resume_delayed_creature() {
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 20));
+        creature_list.add_new_creature(creature_with_initiative("Player 2", 15));
+        creature_list.add_new_creature(creature_with_initiative("Player 3", 10));
+        creature_list.items[0].delayed = true;
+
+        let resumed = creature_list.resume_delayed(0, 2);
+        assert_eq!(resumed, 2);
+        assert_eq!(creature_list.items[2].name, "Player 1");
+        assert_eq!(creature_list.items[2].initiative, Some(10));
+        assert!(!creature_list.items[2].delayed);
+
+        creature_list.sort_creature_list();
+        assert_eq!(creature_list.items[1].name, "Player 3");
+        assert_eq!(creature_list.items[2].name, "Player 1");
+    }
+
+    #[test]
+    fn test_resume_delayed_creature_later_in_the_round() {
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 20));
+        creature_list.add_new_creature(creature_with_initiative("Player 2", 15));
+        creature_list.add_new_creature(creature_with_initiative("Player 3", 10));
+        creature_list.add_new_creature(creature_with_initiative("Player 4", 5));
+        creature_list.items[3].delayed = true;
+
+        // Player 1 has the turn, Player 4 comes back after Player 2
+        let resumed = creature_list.resume_delayed(3, 1);
+        assert_eq!(resumed, 2);
+        let names: Vec<&str> = creature_list
+            .items
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, ["Player 1", "Player 2", "Player 4", "Player 3"]);
+        assert_eq!(creature_list.items[2].initiative, Some(15));
+    }
+
+    #[test]
+    fn test_start_turn_clears_readied_action() {
+        let mut creature = creature_with_initiative("Player 1", 10);
+        creature.readied = Some("When the door opens".to_string());
+        creature.start_turn();
+        assert!(creature.readied.is_none());
+    }
+
+    #[test]
+    fn test_start_combat_requires_initiative() {
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 12));
+        creature_list.add_new_creature(CreatureItem::new_player("Player 2", None));
+        assert_eq!(
+            creature_list.start_combat(),
+            Err(vec!["Player 2".to_string()])
+        );
+        assert!(!creature_list.in_combat());
+
+        creature_list.items[0].initiative = Some(3);
+        assert!(creature_list.start_combat().is_ok());
+        assert_eq!(creature_list.round, 1);
+        assert_eq!(creature_list.items[0].name, "Player 1");
+    }
+
+    #[test]
+    fn test_surprised_creatures_skip_first_round() {
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 12));
+        creature_list.items[0].surprised = true;
+        assert!(creature_list.start_combat().is_ok());
+        assert!(creature_list.skips_turn(0));
+
+        creature_list.next_round();
+        assert_eq!(creature_list.round, 2);
+        assert!(!creature_list.skips_turn(0));
+    }
+
+    #[test]
+    fn test_group_initiative() {
+        let zombie = ApiCreatureSearchItem {
+            name: "Zombie".to_string(),
+            hit_points: Some(22),
+            ..Default::default()
+        };
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(CreatureItem::new_npc(&zombie));
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 10));
+        creature_list.add_new_creature(CreatureItem::new_npc(&zombie));
+        creature_list.add_new_creature(CreatureItem::new_npc(&zombie));
+
+        creature_list.toggle_group_initiative();
+        let zombies: Vec<usize> = creature_list.group_members(
+            creature_list
+                .items
+                .iter()
+                .position(|c| c.name == "Zombie")
+                .unwrap(),
+        );
+        assert_eq!(zombies.len(), 3);
+        assert_eq!(zombies[2] - zombies[0], 2);
+        let initiative = creature_list.items[zombies[0]].initiative;
+        assert!(zombies
+            .iter()
+            .all(|&j| creature_list.items[j].initiative == initiative));
+        // Until the group is collapsed every zombie takes its own turn
+        assert_eq!(creature_list.turn_members(zombies[0]), [zombies[0]]);
+
+        creature_list.toggle_collapsed(zombies[0]);
+        assert!(!creature_list.is_folded(zombies[0]));
+        assert!(creature_list.is_folded(zombies[1]));
+        assert!(creature_list.skips_turn(zombies[2]));
+        assert_eq!(creature_list.turn_members(zombies[0]), zombies);
+
+        // Each zombie keeps its own hit points, the initiative stays shared
+        creature_list.items[zombies[1]].take_damage(5);
+        let hit_points: Vec<u64> = zombies
+            .iter()
+            .map(|&j| creature_list.items[j].hit_points)
+            .collect();
+        assert_eq!(hit_points, [22, 17, 22]);
+        creature_list.roll_group_initiative();
+        let initiative = creature_list.items[zombies[0]].initiative;
+        assert!(zombies
+            .iter()
+            .all(|&j| creature_list.items[j].initiative == initiative));
+    }
+
+    #[test]
+    fn test_initiative_rounds_odd_dexterity_down() {
+        let clumsy = ApiCreatureSearchItem {
+            name: "Zombie".to_string(),
+            dexterity: Some(9),
+            ..Default::default()
+        };
+        for _ in 0..200 {
+            let initiative = CreatureItem::new_npc(&clumsy).initiative.unwrap();
+            assert!((0..=19).contains(&initiative));
+        }
+    }
+
+    #[test]
+    fn test_new_group_member_takes_group_initiative() {
+        let zombie = ApiCreatureSearchItem {
+            name: "Zombie".to_string(),
+            ..Default::default()
+        };
+        let mut creature_list = CreatureList::new(Vec::new());
+        creature_list.add_new_creature(creature_with_initiative("Player 1", 10));
+        creature_list.add_new_creature(CreatureItem::new_npc(&zombie));
+        creature_list.add_new_creature(CreatureItem::new_npc(&zombie));
+        creature_list.toggle_group_initiative();
+
+        let mut late = CreatureItem::new_npc(&zombie);
+        late.initiative = Some(100);
+        creature_list.add_new_creature(late);
+
+        let initiatives: Vec<Option<i64>> = creature_list
+            .items
+            .iter()
+            .filter(|c| c.name == "Zombie")
+            .map(|c| c.initiative)
+            .collect();
+        assert_eq!(initiatives.len(), 3);
+        assert!(initiatives.iter().all(|&i| i == initiatives[0]));
+        assert_ne!(initiatives[0], Some(100));
+        assert!(creature_list
+            .items
+            .windows(2)
+            .all(|pair| pair[0].initiative >= pair[1].initiative));
+    }
+
+    #[test]
+    fn test_players_from_yaml() {
+        let yaml = "
+players:
+  - name: Fighter
+    level: 3
+    hit_points: 28
+  - name: Wizard
+";
+        let players = players_from_yaml(&YamlLoader::load_from_str(yaml).unwrap()[0]);
+        assert_eq!(players[0].level, Some(3));
+        assert!(!players[0].untracked_hp);
+        assert_eq!((players[0].hit_points, players[0].max_hit_points), (28, 28));
+        assert!(players[1].untracked_hp);
+    }
+
+    #[test]
+    fn test_replace_npcs_keeps_players() {
+        let goblin = ApiCreatureSearchItem {
+            name: "Goblin".to_string(),
+            ..Default::default()
+        };
+        let wolf = ApiCreatureSearchItem {
+            name: "Wolf".to_string(),
+            ..Default::default()
+        };
+        let mut creature_list = CreatureList::new(vec![
+            creature_with_initiative("Player 1", 10),
+            CreatureItem::new_npc(&goblin),
+        ]);
+        creature_list.replace_npcs(vec![
+            CreatureItem::new_npc(&wolf),
+            CreatureItem::new_npc(&wolf),
+        ]);
+        let names: Vec<&str> = creature_list
+            .items
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Wolf", "Wolf", "Player 1"]);
+    }
+
+    #[test]
+    fn test_roll_hit_points() {
+        let mut creature = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            hit_points: Some(7),
+            hit_dice: Some("2d6".to_string()),
+            ..Default::default()
+        });
+        creature.roll_hit_points();
+        assert!((2..=12).contains(&creature.max_hit_points));
+        assert_eq!(creature.hit_points, creature.max_hit_points);
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use wtii::dice::DiceExpr;
+
+    #[test]
+    fn test_parse_dice() {
+        let dice: DiceExpr = "2d6 + 3".parse().unwrap();
+        assert_eq!(dice.min(), 5);
+        assert_eq!(dice.max(), 15);
+
+        let dice: DiceExpr = "1d8+1d4-1".parse().unwrap();
+        assert_eq!(dice.min(), 1);
+        assert_eq!(dice.max(), 11);
+
+        let dice: DiceExpr = "d20".parse().unwrap();
+        assert_eq!(dice.max(), 20);
+    }
+
+    #[test]
+    fn test_parse_dice_error() {
+        assert!("".parse::<DiceExpr>().is_err());
+        assert!("2d".parse::<DiceExpr>().is_err());
+        assert!("2d6+".parse::<DiceExpr>().is_err());
+        assert!("1d0".parse::<DiceExpr>().is_err());
+        assert!("-".parse::<DiceExpr>().is_err());
+        assert!("+".parse::<DiceExpr>().is_err());
+        assert!("--2".parse::<DiceExpr>().is_err());
+        assert!("2d6+-1".parse::<DiceExpr>().is_err());
+        assert_eq!("-2".parse::<DiceExpr>().unwrap().max(), -2);
+        assert!("4000000000d6".parse::<DiceExpr>().is_err());
+        assert!("1001d6".parse::<DiceExpr>().is_err());
+        assert!("1d4294967295".parse::<DiceExpr>().is_err());
+        assert!("9223372036854775807".parse::<DiceExpr>().is_err());
+    }
+
+    #[test]
+    fn test_largest_dice() {
+        let dice: DiceExpr = "1000d1000 - 1000d1000".parse().unwrap();
+        assert_eq!(dice.max(), 999_000);
+        assert_eq!(dice.min(), -999_000);
+        assert_eq!(dice.doubled_dice().max(), 1_998_000);
+    }
+
+    #[test]
+    fn test_roll_dice() {
+        let dice: DiceExpr = "3d6+2".parse().unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..100 {
+            let roll = dice.roll_with(&mut rng);
+            assert_eq!(roll.rolls.len(), 3);
+            assert!(roll.total >= dice.min() && roll.total <= dice.max());
+        }
+        assert_eq!(dice.doubled_dice().max(), 38);
+    }
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, CreatureList};
+    use wtii::difficulty::{
+        encounter_multiplier, encounter_summary, parse_challenge_rating, xp_for, Difficulty,
+    };
+
+    fn monster(cr: &str) -> CreatureItem {
+        CreatureItem::new_npc(&ApiCreatureSearchItem {
+            challenge_rating: Some(cr.to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn player(level: u32) -> CreatureItem {
+        let mut creature = CreatureItem::new_player("Player", None);
+        creature.level = Some(level);
+        creature
+    }
+
+    #[test]
+    fn test_challenge_rating_to_xp() {
+        assert_eq!(parse_challenge_rating("1/4"), Some(0.25));
+        assert_eq!(parse_challenge_rating("0.5"), Some(0.5));
+        assert_eq!(parse_challenge_rating("31"), None);
+        assert_eq!(parse_challenge_rating("1/0"), None);
+        assert_eq!(xp_for("0"), Some(10));
+        assert_eq!(xp_for("1/8"), Some(25));
+        assert_eq!(xp_for("1/2"), Some(100));
+        assert_eq!(xp_for("5"), Some(1800));
+        assert_eq!(xp_for("30"), Some(155000));
+        assert_eq!(xp_for("2.5"), None);
+        assert_eq!(xp_for("unknown"), None);
+    }
+
+    #[test]
+    fn test_encounter_multiplier() {
+        assert_eq!(encounter_multiplier(1, 4), 1.0);
+        assert_eq!(encounter_multiplier(4, 4), 2.0);
+        assert_eq!(encounter_multiplier(15, 4), 4.0);
+        assert_eq!(encounter_multiplier(1, 2), 1.5);
+        assert_eq!(encounter_multiplier(1, 6), 0.5);
+        assert_eq!(encounter_multiplier(15, 1), 5.0);
+    }
+
+    #[test]
+    fn test_encounter_summary() {
+        let creature_list = CreatureList::new(vec![
+            player(3),
+            player(3),
+            player(3),
+            player(3),
+            CreatureItem::new_player("Sidekick", None),
+            monster("1/4"),
+            monster("1/4"),
+            monster("1"),
+        ]);
+        let summary = encounter_summary(&creature_list);
+        assert_eq!(summary.party_levels, vec![3, 3, 3, 3]);
+        assert_eq!(summary.monsters, 3);
+        assert_eq!(summary.base_xp, 300);
+        assert_eq!(summary.adjusted_xp, 600);
+        assert_eq!(summary.thresholds, [300, 600, 900, 1600]);
+        assert_eq!(summary.difficulty, Difficulty::Medium);
+        assert_eq!(summary.xp_per_player(), 75);
+    }
+
+    #[test]
+    fn test_encounter_summary_without_party() {
+        let summary = encounter_summary(&CreatureList::new(vec![monster("10")]));
+        assert_eq!(summary.difficulty, Difficulty::Trivial);
+        assert_eq!(summary.xp_per_player(), 0);
+    }
+}
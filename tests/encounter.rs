@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, CreatureList};
+    use wtii::encounter::{load_templates, EncounterMonster, EncounterTemplate, HpMode};
+
+    fn template() -> EncounterTemplate {
+        EncounterTemplate {
+            name: "Goblin ambush".to_string(),
+            notes: Some("They hide in the bushes".to_string()),
+            hp_mode: HpMode::Rolled,
+            monsters: vec![
+                EncounterMonster {
+                    name: "Goblin".to_string(),
+                    count: 4,
+                },
+                EncounterMonster {
+                    name: "Goblin Boss".to_string(),
+                    count: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_template_yaml_round_trip() {
+        let yaml = template().to_yaml();
+        assert_eq!(EncounterTemplate::from_yaml(&yaml), Ok(template()));
+    }
+
+    #[test]
+    fn test_template_from_yaml_defaults_count() {
+        let template =
+            EncounterTemplate::from_yaml("name: Wolves\nmonsters:\n  - name: Wolf\n").unwrap();
+        assert_eq!(template.monsters[0].count, 1);
+        assert_eq!(template.notes, None);
+        assert_eq!(template.hp_mode, HpMode::Average);
+        assert!(EncounterTemplate::from_yaml("monsters: []").is_err());
+    }
+
+    #[test]
+    fn test_save_template() {
+        let dir = std::env::temp_dir().join("wtii-test-save-template");
+        let _ = fs::remove_dir_all(&dir);
+        let path = template().save(&dir).unwrap();
+        assert_eq!(path, dir.join("goblin-ambush.yml"));
+        let saved = fs::read_to_string(&path).unwrap();
+        assert_eq!(EncounterTemplate::from_yaml(&saved), Ok(template()));
+
+        let mut renamed = template();
+        renamed.name = "Goblin Ambush".to_string();
+        let error = renamed.save(&dir).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), saved);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_template_limits() {
+        let yaml = "name: Horde\nmonsters:\n  - name: Goblin\n    count: 1000000";
+        assert!(EncounterTemplate::from_yaml(yaml).is_err());
+        let yaml = "name: Horde\nmonsters:\n  - name: Goblin\n    count: 100";
+        assert_eq!(
+            EncounterTemplate::from_yaml(yaml).unwrap().monsters[0].count,
+            100
+        );
+
+        let mut nameless = template();
+        nameless.name = "!!!".to_string();
+        let dir = std::env::temp_dir().join("wtii-test-template-limits");
+        let error = nameless.save(&dir).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(!dir.join(".yml").exists());
+    }
+
+    #[test]
+    fn test_load_templates_sorted_by_name() {
+        let dir = std::env::temp_dir().join("wtii-test-load-templates");
+        let _ = fs::remove_dir_all(&dir);
+        let wolves = EncounterTemplate::from_yaml("name: Wolves\nmonsters: []").unwrap();
+        wolves.save(&dir).unwrap();
+        template().save(&dir).unwrap();
+        fs::write(dir.join("broken.yml"), "monsters: [").unwrap();
+        fs::write(dir.join("notes.txt"), "name: Not a template").unwrap();
+
+        let names: Vec<String> = load_templates(&dir).into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["Goblin ambush", "Wolves"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_template_from_creature_list() {
+        let npc = |name: &str| {
+            CreatureItem::new_npc(&ApiCreatureSearchItem {
+                name: name.to_string(),
+                ..Default::default()
+            })
+        };
+        let creature_list = CreatureList::new(vec![
+            npc("Goblin"),
+            CreatureItem::new_player("Player 1", None),
+            npc("Goblin Boss"),
+            npc("Goblin"),
+        ]);
+        let template = EncounterTemplate::from_creature_list("Ambush", &creature_list);
+        assert_eq!(
+            template.to_string(),
+            "Ambush: 2x Goblin, 1x Goblin Boss (average HP)"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem};
+    use wtii::health::{hp_gauge, HealthConfig};
+    use yaml_rust2::YamlLoader;
+
+    fn goblin(hit_points: u64) -> CreatureItem {
+        let mut goblin = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: "Goblin".to_string(),
+            hit_points: Some(20),
+            ..Default::default()
+        });
+        goblin.hit_points = hit_points;
+        goblin
+    }
+
+    fn config() -> HealthConfig {
+        let yaml = "
+health:
+  thresholds:
+    - percent: 25
+      label: Critical
+      color: red
+    - percent: 50
+      label: Bloodied
+      color: '#ffa500'
+    - percent: 75
+      label: Broken
+      color: not-a-color
+  morale: 30
+";
+        HealthConfig::from_yaml(&YamlLoader::load_from_str(yaml).unwrap()[0])
+    }
+
+    #[test]
+    fn test_hp_gauge() {
+        assert_eq!(hp_gauge(20, 20, 10), "██████████");
+        assert_eq!(hp_gauge(10, 20, 10), "█████░░░░░");
+        assert_eq!(hp_gauge(1, 20, 10), "█░░░░░░░░░");
+        assert_eq!(hp_gauge(0, 20, 10), "░░░░░░░░░░");
+        assert_eq!(hp_gauge(0, 0, 4), "░░░░");
+    }
+
+    #[test]
+    fn test_default_bloodied_at_half() {
+        let config = HealthConfig::default();
+        assert!(config.threshold(&goblin(11)).is_none());
+        assert_eq!(config.threshold(&goblin(10)).unwrap().label, "Bloodied");
+        assert!(config.threshold(&goblin(0)).is_none());
+        assert!(!config.would_flee(&goblin(1)));
+    }
+
+    #[test]
+    fn test_configured_thresholds() {
+        let config = config();
+        assert_eq!(config.thresholds.len(), 2);
+        assert_eq!(config.threshold(&goblin(10)).unwrap().label, "Bloodied");
+        assert_eq!(
+            config.threshold(&goblin(10)).unwrap().color,
+            Color::Rgb(255, 165, 0)
+        );
+        let critical = config.threshold(&goblin(5)).unwrap();
+        assert_eq!(critical.label, "Critical");
+        assert_eq!(critical.color, Color::Red);
+    }
+
+    #[test]
+    fn test_morale() {
+        let config = config();
+        assert!(!config.would_flee(&goblin(7)));
+        assert!(config.would_flee(&goblin(6)));
+        assert!(!config.would_flee(&goblin(0)));
+        assert!(config.breaks_morale(&goblin(6), 7));
+        assert!(!config.breaks_morale(&goblin(5), 6));
+
+        let mut player = CreatureItem::new_player("Fighter", None);
+        player.set_max_hit_points(20);
+        player.hit_points = 2;
+        assert!(!config.would_flee(&player));
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod tests {
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, CreatureList, Status};
+    use wtii::history::{deletions, Command, Edit, History};
+
+    fn creature_list() -> CreatureList {
+        let mut player = CreatureItem::new_player("Player 1", None);
+        player.initiative = Some(10);
+        player.max_hit_points = 10;
+        player.hit_points = 10;
+        let mut other = CreatureItem::new_player("Player 2", None);
+        other.initiative = Some(5);
+        CreatureList::new(vec![player, other])
+    }
+
+    fn hit_points(id: u64, before: u64, after: u64) -> Command {
+        Command::Edit {
+            id,
+            name: "Player 1".to_string(),
+            edit: Edit::HitPoints { before, after },
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_hit_points() {
+        let mut creature_list = creature_list();
+        let mut history = History::default();
+        let id = creature_list.items[0].id;
+
+        creature_list.items[0].hit_points = 0;
+        creature_list.items[0].status = Status::Dead;
+        history.record(hit_points(id, 10, 9));
+        history.record(hit_points(id, 9, 0));
+        assert_eq!(history.done().count(), 1);
+        assert_eq!(
+            history.done().next().unwrap().to_string(),
+            "Player 1: HP 10 -> 0"
+        );
+
+        history.undo(&mut creature_list);
+        assert_eq!(creature_list.items[0].hit_points, 10);
+        assert_eq!(creature_list.items[0].status, Status::Alive);
+
+        history.redo(&mut creature_list);
+        assert_eq!(creature_list.items[0].hit_points, 0);
+        assert_eq!(creature_list.items[0].status, Status::Dead);
+        assert!(history.redo(&mut creature_list).is_none());
+    }
+
+    #[test]
+    fn test_undo_delete_and_insert() {
+        let mut creature_list = creature_list();
+        let mut history = History::default();
+
+        let creature = creature_list.items.remove(0);
+        history.record(Command::Delete { index: 0, creature });
+        let mut new = CreatureItem::new_player("Player 3", None);
+        new.id = creature_list.next_id();
+        history.record(Command::Insert {
+            creature: new.clone(),
+        });
+        creature_list.add_new_creature(new);
+        assert_eq!(creature_list.items.len(), 2);
+
+        assert_eq!(
+            history.undo(&mut creature_list).unwrap().to_string(),
+            "Add Player 3"
+        );
+        assert_eq!(
+            history.undo(&mut creature_list).unwrap().to_string(),
+            "Delete Player 1"
+        );
+        let names: Vec<&str> = creature_list
+            .items
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Player 1", "Player 2"]);
+        assert!(history.undo(&mut creature_list).is_none());
+
+        history.redo(&mut creature_list);
+        assert_eq!(creature_list.items.len(), 1);
+        assert_eq!(history.undone().count(), 1);
+
+        // A new change drops what can be redone
+        history.record(hit_points(creature_list.items[0].id, 1, 2));
+        assert_eq!(history.undone().count(), 0);
+    }
+
+    #[test]
+    fn test_undo_initiative_follows_the_creature() {
+        let mut creature_list = creature_list();
+        let mut history = History::default();
+        let id = creature_list.items[1].id;
+
+        history.record(Command::Edit {
+            id,
+            name: "Player 2".to_string(),
+            edit: Edit::Initiative {
+                before: Some(5),
+                after: Some(20),
+            },
+        });
+        creature_list.set_initiative(1, Some(20));
+        assert_eq!(creature_list.items[0].id, id);
+
+        history.undo(&mut creature_list);
+        assert_eq!(creature_list.items[1].id, id);
+        assert_eq!(creature_list.items[1].initiative, Some(5));
+    }
+
+    #[test]
+    fn test_undo_batch_as_one() {
+        let mut creature_list = creature_list();
+        let mut goblin = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: "Goblin".to_string(),
+            ..Default::default()
+        });
+        goblin.initiative = Some(7);
+        creature_list.add_new_creature(goblin);
+        let before: Vec<u64> = creature_list.items.iter().map(|c| c.id).collect();
+        let mut history = History::default();
+
+        // Swap the goblin for a wolf the way loading an encounter does
+        let index = creature_list
+            .items
+            .iter()
+            .position(|c| c.name == "Goblin")
+            .unwrap();
+        let goblin = creature_list.items[index].clone();
+        let mut wolf = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: "Wolf".to_string(),
+            ..Default::default()
+        });
+        wolf.id = creature_list.next_id();
+        history.record(Command::Batch {
+            name: "Load Wolves".to_string(),
+            commands: vec![
+                Command::Delete {
+                    index,
+                    creature: goblin,
+                },
+                Command::Insert {
+                    creature: wolf.clone(),
+                },
+            ],
+        });
+        creature_list.replace_npcs(vec![wolf]);
+
+        assert_eq!(
+            history.undo(&mut creature_list).unwrap().to_string(),
+            "Load Wolves"
+        );
+        let ids: Vec<u64> = creature_list.items.iter().map(|c| c.id).collect();
+        assert_eq!(ids, before);
+
+        history.redo(&mut creature_list);
+        assert!(creature_list.items.iter().any(|c| c.name == "Wolf"));
+        assert!(!creature_list.items.iter().any(|c| c.name == "Goblin"));
+    }
+
+    #[test]
+    fn test_undo_starting_over() {
+        let mut creature_list = creature_list();
+        let before: Vec<u64> = creature_list.items.iter().map(|c| c.id).collect();
+        let mut history = History::default();
+
+        let mut fresh = CreatureList::new(vec![CreatureItem::new_player("Player 1", None)]);
+        fresh.renumber_after(&creature_list);
+        assert!(!before.contains(&fresh.items[0].id));
+        let mut commands = deletions(&creature_list, |_| true);
+        commands.push(Command::Insert {
+            creature: fresh.items[0].clone(),
+        });
+        let fresh_id = fresh.items[0].id;
+        creature_list = fresh;
+        history.record(Command::Batch {
+            name: "New encounter".to_string(),
+            commands,
+        });
+
+        history.undo(&mut creature_list);
+        let ids: Vec<u64> = creature_list.items.iter().map(|c| c.id).collect();
+        assert_eq!(ids, before);
+        history.redo(&mut creature_list);
+        let ids: Vec<u64> = creature_list.items.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [fresh_id]);
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod tests {
+    use wtii::dice::DiceExpr;
+    use wtii::mob::{attackers_per_hit, resolve_mob_attack, MobMode};
+
+    #[test]
+    fn test_attackers_per_hit() {
+        assert_eq!(attackers_per_hit(3), Some(1));
+        assert_eq!(attackers_per_hit(12), Some(2));
+        assert_eq!(attackers_per_hit(14), Some(3));
+        assert_eq!(attackers_per_hit(19), Some(10));
+        assert_eq!(attackers_per_hit(20), Some(20));
+        assert_eq!(attackers_per_hit(21), None);
+    }
+
+    #[test]
+    fn test_resolve_mob_attack_with_table() {
+        let damage: DiceExpr = "1d6+1".parse().unwrap();
+        // +3 against AC 15 needs a 12, two attackers per hit
+        let mob_attack = resolve_mob_attack(10, 3, 15, Some(&damage), MobMode::Table);
+        assert_eq!(mob_attack.d20_needed, 12);
+        assert_eq!(mob_attack.hits, 5);
+        assert_eq!(mob_attack.damage_rolls.len(), 5);
+        assert!(mob_attack.total_damage >= 10 && mob_attack.total_damage <= 35);
+    }
+
+    #[test]
+    fn test_resolve_mob_attack_rolling_all() {
+        let mob_attack = resolve_mob_attack(10, 3, 15, None, MobMode::RollAll);
+        assert!(mob_attack.hits <= 10);
+        assert!(mob_attack.criticals <= mob_attack.hits);
+        assert_eq!(mob_attack.total_damage, 0);
+    }
+}
@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use wtii::checks::Ability;
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem};
+    use wtii::ongoing::{
+        parse_save, suppress_effects, trigger_effects_with, HitPointsEffect, OngoingEffect, Trigger,
+    };
+
+    fn troll() -> CreatureItem {
+        let mut creature = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: "Troll".to_string(),
+            hit_points: Some(84),
+            ..Default::default()
+        });
+        creature.hit_points = 50;
+        let mut regeneration = OngoingEffect::new("Regeneration", Trigger::TurnStart);
+        regeneration.hit_points = Some("+10".parse().unwrap());
+        regeneration.suppressed_by = vec!["acid".to_string(), "fire".to_string()];
+        creature.effects.push(regeneration);
+        creature
+    }
+
+    #[test]
+    fn test_parse_effect() {
+        let healing: HitPointsEffect = "+10".parse().unwrap();
+        assert!(healing.healing);
+        assert_eq!(healing.damage_type, None);
+        assert_eq!(healing.to_string(), "heals 10");
+
+        let damage: HitPointsEffect = "2d6 Fire".parse().unwrap();
+        assert!(!damage.healing);
+        assert_eq!(damage.expr, "2d6");
+        assert_eq!(damage.damage_type, Some("fire".to_string()));
+        assert_eq!(damage.to_string(), "2d6 fire damage");
+
+        assert!("-1d4".parse::<HitPointsEffect>().is_ok());
+        assert!("fire".parse::<HitPointsEffect>().is_err());
+
+        assert_eq!(parse_save("CON 13"), Ok((Ability::Constitution, 13)));
+        assert!(parse_save("CON").is_err());
+        assert!(parse_save("CON high").is_err());
+
+        assert_eq!("end".parse::<Trigger>(), Ok(Trigger::TurnEnd));
+        assert_eq!("".parse::<Trigger>(), Ok(Trigger::TurnStart));
+        assert!("middle".parse::<Trigger>().is_err());
+    }
+
+    #[test]
+    fn test_regeneration_fires_on_its_trigger() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut creature = troll();
+
+        assert!(trigger_effects_with(&mut creature, Trigger::TurnEnd, &mut rng).is_empty());
+        assert_eq!(creature.hit_points, 50);
+
+        let outcomes = trigger_effects_with(&mut creature, Trigger::TurnStart, &mut rng);
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].change, 10);
+        assert_eq!(creature.hit_points, 60);
+        assert_eq!(outcomes[0].to_string(), "Troll: Regeneration heals 10");
+        assert_eq!(creature.effects.len(), 1);
+    }
+
+    #[test]
+    fn test_damage_type_suppresses_regeneration_once() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut creature = troll();
+
+        suppress_effects(&mut creature, "cold");
+        assert!(!creature.effects[0].suppressed);
+        suppress_effects(&mut creature, "Fire");
+        assert!(creature.effects[0].suppressed);
+
+        let outcomes = trigger_effects_with(&mut creature, Trigger::TurnStart, &mut rng);
+        assert!(outcomes[0].suppressed);
+        assert_eq!(creature.hit_points, 50);
+        assert_eq!(outcomes[0].to_string(), "Troll: Regeneration suppressed");
+
+        trigger_effects_with(&mut creature, Trigger::TurnStart, &mut rng);
+        assert_eq!(creature.hit_points, 60);
+    }
+
+    #[test]
+    fn test_damage_applies_defenses() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut creature = troll();
+        creature.effects.clear();
+        creature.damage_resistances = Some("fire".to_string());
+        let mut burning = OngoingEffect::new("Burning", Trigger::TurnEnd);
+        burning.hit_points = Some("10 fire".parse().unwrap());
+        creature.effects.push(burning);
+
+        let outcomes = trigger_effects_with(&mut creature, Trigger::TurnEnd, &mut rng);
+        assert_eq!(outcomes[0].change, -5);
+        assert_eq!(creature.hit_points, 45);
+        assert_eq!(outcomes[0].to_string(), "Troll: Burning takes 5 damage");
+    }
+
+    #[test]
+    fn test_successful_save_ends_effect() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut creature = troll();
+        creature.effects.clear();
+        let mut poison = OngoingEffect::new("Poisoned", Trigger::TurnEnd);
+        poison.save = Some((Ability::Constitution, 13));
+        creature.effects.push(poison);
+
+        creature.constitution_save = Some(-20);
+        let outcomes = trigger_effects_with(&mut creature, Trigger::TurnEnd, &mut rng);
+        assert!(!outcomes[0].ended());
+        assert_eq!(creature.effects.len(), 1);
+
+        creature.constitution_save = Some(20);
+        let outcomes = trigger_effects_with(&mut creature, Trigger::TurnEnd, &mut rng);
+        let save = outcomes[0].save.as_ref().unwrap();
+        assert!(save.success());
+        assert_eq!(save.bonus, 20);
+        assert!(outcomes[0].ended());
+        assert!(outcomes[0].to_string().ends_with("success, ends"));
+        assert!(creature.effects.is_empty());
+    }
+}
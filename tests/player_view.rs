@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use wtii::control::{response_body, EncounterState};
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, CreatureList};
+    use wtii::player_view::PlayerView;
+
+    fn encounter() -> CreatureList {
+        let mut fighter = CreatureItem::new_player("Fighter", None);
+        fighter.initiative = Some(18);
+        fighter.set_max_hit_points(30);
+        fighter.hit_points = 12;
+        let mut rogue = CreatureItem::new_player("Rogue", None);
+        rogue.initiative = Some(3);
+
+        let mut goblin = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: "Goblin <Boss>".to_string(),
+            hit_points: Some(20),
+            ..Default::default()
+        });
+        goblin.initiative = Some(12);
+        goblin.take_damage(11);
+        goblin.conditions.push("Prone".to_string());
+
+        let mut assassin = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: "Assassin".to_string(),
+            ..Default::default()
+        });
+        assassin.initiative = Some(15);
+        assassin.hidden = true;
+
+        let mut list = CreatureList::new(vec![fighter, goblin, assassin, rogue]);
+        list.sort_creature_list();
+        list
+    }
+
+    #[test]
+    fn test_health_descriptor_follows_the_thresholds() {
+        let mut list = encounter();
+        let goblin = |list: &CreatureList| EncounterState::new(list, None).creatures[2].clone();
+        assert_eq!(goblin(&list).descriptor, "Bloodied");
+
+        list.health.thresholds[0].percent = 25;
+        list.health.thresholds[0].label = "Critical".to_string();
+        assert_eq!(goblin(&list).descriptor, "Hurt");
+        list.items[2].hit_points = 5;
+        assert_eq!(goblin(&list).descriptor, "Critical");
+        list.items[2].hit_points = 20;
+        assert_eq!(goblin(&list).descriptor, "Unhurt");
+        list.items[2].take_damage(20);
+        assert_eq!(goblin(&list).descriptor, "Down");
+    }
+
+    #[test]
+    fn test_player_view_hides_npc_hit_points_and_hidden_creatures() {
+        let list = encounter();
+        let view = PlayerView::new(&EncounterState::new(&list, Some(2)));
+
+        let names: Vec<&str> = view.creatures.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Fighter", "Goblin <Boss>", "Rogue"]);
+        assert_eq!(view.creatures[0].health, "12/30");
+        assert_eq!(view.creatures[2].health, "");
+        assert_eq!(view.creatures[1].health, "Bloodied");
+        assert_eq!(view.creatures[1].conditions, ["Prone"]);
+        assert!(view.creatures[1].current);
+        assert!(!view.creatures[0].current);
+    }
+
+    #[test]
+    fn test_hidden_creature_turn_is_not_highlighted() {
+        let list = encounter();
+        assert_eq!(list.items[1].name, "Assassin");
+        let view = PlayerView::new(&EncounterState::new(&list, Some(1)));
+        assert!(view.creatures.iter().all(|c| !c.current));
+    }
+
+    #[test]
+    fn test_player_page() {
+        let mut list = encounter();
+        list.round = 2;
+        let state = EncounterState::new(&list, Some(0));
+
+        let (content_type, html) = response_body("/players", &state);
+        assert!(content_type.starts_with("text/html"));
+        assert!(html.contains("<h1>Round 2</h1>"));
+        assert!(html.contains("<tr class=\"current\"><td>Fighter</td><td>12/30</td>"));
+        assert!(html.contains("<td>Goblin &lt;Boss&gt;</td><td>Bloodied</td><td>Prone</td>"));
+        assert!(!html.contains("Assassin"));
+
+        let (_, json) = response_body("/players.json", &state);
+        let view: PlayerView = serde_json::from_str(&json).unwrap();
+        assert_eq!(view, PlayerView::new(&state));
+    }
+}
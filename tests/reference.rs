@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use wtii::creature::CreatureItem;
+    use wtii::reference::{find_condition, offline_conditions, SRD_CONDITIONS};
+
+    #[test]
+    fn test_offline_conditions() {
+        assert_eq!(offline_conditions("").len(), SRD_CONDITIONS.len());
+        let res = offline_conditions("RESTR");
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].name, "Restrained");
+    }
+
+    #[test]
+    fn test_find_condition() {
+        assert!(find_condition("grappled").is_some());
+        assert!(find_condition("Hasted").is_none());
+    }
+
+    #[test]
+    fn test_toggle_condition() {
+        let mut creature = CreatureItem::new_player("Player 1", None);
+        creature.toggle_condition("Prone");
+        assert!(creature.has_condition("prone"));
+        creature.toggle_condition("prone");
+        assert!(creature.conditions.is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use wtii::creature::{ApiCreatureSearchItem, CreatureItem, CreatureList, Skills, Status};
+    use wtii::senses::{
+        parse_passive_perception, parse_senses, passive_perception, senses_overview, SensesColumn,
+    };
+
+    fn npc(name: &str, wisdom: i64, senses: &str) -> CreatureItem {
+        CreatureItem::new_npc(&ApiCreatureSearchItem {
+            name: name.to_string(),
+            wisdom: Some(wisdom),
+            senses: Some(senses.to_string()),
+            languages: Some("Common".to_string()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_parse_senses() {
+        let senses = parse_senses("blindsight 10 ft., darkvision 120 ft., passive Perception 13");
+        assert_eq!(senses.blindsight, Some(10));
+        assert_eq!(senses.darkvision, Some(120));
+        assert_eq!(senses.tremorsense, None);
+        assert_eq!(senses.truesight, None);
+    }
+
+    #[test]
+    fn test_passive_perception() {
+        assert_eq!(passive_perception(&npc("Goblin", 8, "")), 9);
+        let creature = CreatureItem::new_npc(&ApiCreatureSearchItem {
+            wisdom: Some(10),
+            skills: Some(Skills {
+                perception: Some(6),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        assert_eq!(passive_perception(&creature), 16);
+
+        // The stat block wins over the bonus, e.g. for Observant
+        let creature = npc("Owlbear", 12, "darkvision 60 ft., passive Perception 13");
+        assert_eq!(passive_perception(&creature), 13);
+        assert_eq!(parse_passive_perception("passive Perception 20"), Some(20));
+        assert_eq!(parse_passive_perception("darkvision 60 ft."), None);
+    }
+
+    #[test]
+    fn test_senses_overview_sorts_living_creatures() {
+        let mut dead = npc("Zombie", 6, "darkvision 60 ft.");
+        dead.status = Status::Dead;
+        let creature_list = CreatureList::new(vec![
+            npc("Goblin", 8, "darkvision 60 ft."),
+            npc("Owlbear", 12, "darkvision 60 ft., passive Perception 13"),
+            npc("Ankheg", 11, "darkvision 60 ft., tremorsense 60 ft."),
+            dead,
+        ]);
+
+        let rows = senses_overview(&creature_list, SensesColumn::PassivePerception);
+        let names: Vec<&str> = rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, vec!["Owlbear", "Ankheg", "Goblin"]);
+
+        let rows = senses_overview(&creature_list, SensesColumn::Tremorsense);
+        assert_eq!(rows[0].name, "Ankheg");
+        assert_eq!(rows[0].senses.tremorsense, Some(60));
+
+        let rows = senses_overview(&creature_list, SensesColumn::Name);
+        assert_eq!(rows[0].name, "Ankheg");
+        assert_eq!(SensesColumn::Languages.next(), SensesColumn::Name);
+    }
+}
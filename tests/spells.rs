@@ -0,0 +1,73 @@
+use std::fs;
+
+fn load_mock_creature_json(file: &str) -> String {
+    fs::read_to_string(format!("tests/fixtures/{}", file))
+        .unwrap_or_else(|_| panic!("Failed to read the json file: {}", file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wtii::abilities::Rest;
+    use wtii::api::parse_json_response;
+    use wtii::creature::CreatureItem;
+    use wtii::spells::{ordinal, parse_spell_slots, spell_slug_from_url, SpellSlot};
+
+    #[test]
+    fn test_parse_spell_slots() {
+        let desc = "cantrips (at will): fire bolt\n\n1st level (4 slots): mage armor\n\n\
+                    2nd level (3 slots): darkness\n\n5th level (1 slot): cone of cold";
+        let slots = parse_spell_slots(desc);
+        assert_eq!(
+            slots,
+            vec![
+                SpellSlot::new(1, 4),
+                SpellSlot::new(2, 3),
+                SpellSlot::new(5, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_warlock_spell_slots() {
+        let mut slots = parse_spell_slots("1st-5th level (3 5th-level slots): hex, fly");
+        assert_eq!(slots, vec![SpellSlot::pact(5, 3)]);
+        assert_eq!(slots[0].to_string(), "5th level (pact): 3/3");
+
+        // Pact slots come back on a short rest
+        assert!(slots[0].spend());
+        slots[0].rest(Rest::Short);
+        assert_eq!(slots[0].remaining, 3);
+    }
+
+    #[test]
+    fn test_spell_slots_from_creature() {
+        let data = load_mock_creature_json("lots_of_resp.json");
+        let creatures = parse_json_response(&data).unwrap();
+        let guardian = creatures
+            .iter()
+            .find(|c| c.name == "Spectral Guardian")
+            .unwrap();
+        let mut creature = CreatureItem::new_npc(guardian);
+        assert_eq!(creature.spell_slots.len(), 5);
+        assert!(creature.is_spellcaster());
+
+        assert!(creature.spell_slots[4].spend());
+        assert!(!creature.spell_slots[4].spend());
+        creature.rest(Rest::Short);
+        assert_eq!(creature.spell_slots[4].remaining, 0);
+        creature.rest(Rest::Long);
+        assert_eq!(creature.spell_slots[4].remaining, 1);
+    }
+
+    #[test]
+    fn test_spell_slug_from_url() {
+        assert_eq!(
+            spell_slug_from_url("https://api.open5e.com/v1/spells/fire-bolt/"),
+            Some("fire-bolt")
+        );
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(11), "11th");
+    }
+}
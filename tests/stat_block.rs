@@ -0,0 +1,79 @@
+use std::fs;
+
+fn load_crimson_drake() -> wtii::creature::ApiCreatureSearchItem {
+    let data = fs::read_to_string("tests/fixtures/single_creature_response.json")
+        .expect("Failed to read the json file");
+    wtii::api::parse_json_response(&data).unwrap().remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use wtii::creature::CreatureItem;
+    use wtii::stat_block::{
+        render_search_item, render_stat_block, render_stat_blocks, StatBlockFormat,
+    };
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("md".parse(), Ok(StatBlockFormat::Markdown));
+        assert_eq!("JSON".parse(), Ok(StatBlockFormat::Json));
+        assert_eq!("text".parse(), Ok(StatBlockFormat::Text));
+        assert!("yaml".parse::<StatBlockFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = render_search_item(&load_crimson_drake(), StatBlockFormat::Text);
+        assert!(text.contains("Name: Crimson Drake\nHP: 54"));
+        assert!(text.contains("AC: 14"));
+        assert!(text.contains("\n===Challenge Rating===\n1 (200 XP)"));
+        assert!(text.contains("\n===Actions===\nMultiattack"));
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let markdown = render_search_item(&load_crimson_drake(), StatBlockFormat::Markdown);
+        assert!(markdown.contains("# Crimson Drake\n"));
+        assert!(markdown.contains("- **HP:** 54\n- **AC:** 14"));
+        assert!(markdown.contains("## Challenge Rating\n\n1 (200 XP)"));
+        assert!(markdown.contains("\n\n---\n\n"));
+        assert!(!markdown.contains("-----"));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json = render_search_item(&load_crimson_drake(), StatBlockFormat::Json);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["name"], "Crimson Drake");
+        assert_eq!(value["hp"], "54");
+        assert_eq!(value["challenge_rating"], "1 (200 XP)");
+        assert_eq!(value["actions"].as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_render_several_stat_blocks() {
+        let drake = CreatureItem::new_npc(&load_crimson_drake());
+        let creatures = vec![drake.clone(), drake.clone()];
+
+        let value: Value =
+            serde_json::from_str(&render_stat_blocks(&creatures, StatBlockFormat::Json)).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 2);
+
+        let one: Value =
+            serde_json::from_str(&render_stat_blocks(&creatures[..1], StatBlockFormat::Json))
+                .unwrap();
+        assert!(one.is_array());
+
+        let text = render_stat_blocks(&creatures, StatBlockFormat::Text);
+        assert_eq!(
+            text,
+            format!(
+                "{}\n\n{}",
+                render_stat_block(&drake, StatBlockFormat::Text),
+                render_stat_block(&drake, StatBlockFormat::Text)
+            )
+        );
+    }
+}
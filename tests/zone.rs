@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod tests {
+    use wtii::combat_log::EventKind;
+    use wtii::creature::CreatureItem;
+    use wtii::zone::{expire_zones, zone_reminders, ZoneEffect};
+
+    fn cleric() -> CreatureItem {
+        let mut creature = CreatureItem::new_player("Cleric", None);
+        creature.id = 3;
+        creature
+    }
+
+    fn spirit_guardians() -> ZoneEffect {
+        let mut zone = ZoneEffect::new("Spirit Guardians", &cleric(), 2, 10);
+        zone.reminder = Some("WIS save DC 15 or 3d8 radiant".to_string());
+        zone
+    }
+
+    #[test]
+    fn test_rounds_left() {
+        let zone = spirit_guardians();
+        assert_eq!(zone.ends(), 12);
+        assert_eq!(zone.rounds_left(0), 10);
+        assert_eq!(zone.rounds_left(2), 10);
+        assert_eq!(zone.rounds_left(11), 1);
+        assert_eq!(zone.rounds_left(13), 0);
+        assert_eq!(zone.summary(11), "Spirit Guardians (Cleric, 1 round left)");
+        assert_eq!(zone.summary(5), "Spirit Guardians (Cleric, 7 rounds left)");
+        assert_eq!(zone.to_string(), "Spirit Guardians (Cleric, 10 rounds)");
+    }
+
+    #[test]
+    fn test_expires_on_owners_turn() {
+        let zone = spirit_guardians();
+        assert!(!zone.expired(11, 3));
+        assert!(!zone.expired(12, 1));
+        assert!(zone.expired(12, 3));
+        // The owner may be gone, then it ends once the round has passed
+        assert!(zone.expired(13, 1));
+    }
+
+    #[test]
+    fn test_expire_zones() {
+        let wall = ZoneEffect::new(
+            "Wall of Fire",
+            &CreatureItem::new_player("Mage", None),
+            1,
+            10,
+        );
+        let mut zones = vec![spirit_guardians(), wall.clone()];
+
+        assert!(expire_zones(&mut zones, 5, 3).is_empty());
+        assert_eq!(zones.len(), 2);
+
+        let expired = expire_zones(&mut zones, 12, 3);
+        assert_eq!(expired.len(), 2);
+        assert_eq!(expired[0].name, "Spirit Guardians");
+        assert_eq!(expired[1], wall);
+        assert!(zones.is_empty());
+    }
+
+    #[test]
+    fn test_zone_reminders() {
+        let wall = ZoneEffect::new("Wall of Fire", &cleric(), 1, 10);
+        let zones = vec![spirit_guardians(), wall];
+        assert_eq!(
+            zone_reminders(&zones),
+            vec!["Spirit Guardians: WIS save DC 15 or 3d8 radiant".to_string()]
+        );
+
+        let kind = EventKind::ZoneEnded {
+            creature: "Cleric".to_string(),
+            zone: "Spirit Guardians".to_string(),
+        };
+        assert_eq!(kind.to_string(), "Spirit Guardians of Cleric ends");
+    }
+}